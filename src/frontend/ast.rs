@@ -1,3 +1,7 @@
+use num_bigint::BigInt;
+
+use super::span::Span;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum NodeType {
     // STATEMENTS
@@ -5,8 +9,17 @@ pub enum NodeType {
     VarDeclaration(VarDeclaration),
     FunctionDeclaration(FunctionDeclaration),
     IfStatement(IfStatement),
+    /// A `{ ... }` block on its own, with a scope of its own.
+    BlockStatement(BlockStatement),
     /// `ForStatement(init, test, update, body)`
     ForStatement(ForStatement),
+    /// `WhileStatement(test, body)`
+    WhileStatement(WhileStatement),
+    /// `DoWhileStatement(body, test)`
+    DoWhileStatement(DoWhileStatement),
+    BreakStatement(BreakStatement),
+    ContinueStatement(ContinueStatement),
+    ReturnStatement(ReturnStatement),
     /// TryCatchStatement(body, alternate)
     TryCatchStatement(TryCatchStatement),
 
@@ -14,19 +27,66 @@ pub enum NodeType {
     AssignmentExpr(AssignmentExpr),
     MemberExpr(MemberExpr),
     CallExpr(CallExpr),
+    /// `fn (a, b) { ... }` or `(a, b) => ...`, a function without a name of its own.
+    FunctionExpr(FunctionExpr),
 
     // LITERALS
     Property(Property),
     ObjectLiteral(ObjectLiteral),
+    ArrayLiteral(ArrayLiteral),
     NumericLiteral(NumericLiteral),
+    IntegerLiteral(IntegerLiteral),
+    BigIntLiteral(BigIntLiteral),
     Identifier(Identifier),
     StringLiteral(StringLiteral),
+    /// TemplateLiteral(quasis, expressions)
+    TemplateLiteral(TemplateLiteral),
     BinaryExpr(BinaryExpr),
+    /// `!x`, `-x`, `+x` and `typeof x`
+    UnaryExpr(UnaryExpr),
+    /// `&&` and `||`, which only evaluate their right side when they need to.
+    LogicalExpr(LogicalExpr),
+}
+
+impl NodeType {
+    pub fn span(&self) -> Span {
+        match self {
+            NodeType::Program(node) => node.span,
+            NodeType::VarDeclaration(node) => node.span,
+            NodeType::FunctionDeclaration(node) => node.span,
+            NodeType::IfStatement(node) => node.span,
+            NodeType::BlockStatement(node) => node.span,
+            NodeType::ForStatement(node) => node.span,
+            NodeType::WhileStatement(node) => node.span,
+            NodeType::DoWhileStatement(node) => node.span,
+            NodeType::BreakStatement(node) => node.span,
+            NodeType::ContinueStatement(node) => node.span,
+            NodeType::ReturnStatement(node) => node.span,
+            NodeType::TryCatchStatement(node) => node.span,
+            NodeType::AssignmentExpr(node) => node.span,
+            NodeType::MemberExpr(node) => node.span,
+            NodeType::CallExpr(node) => node.span,
+            NodeType::FunctionExpr(node) => node.span,
+            NodeType::Property(node) => node.span,
+            NodeType::ObjectLiteral(node) => node.span,
+            NodeType::ArrayLiteral(node) => node.span,
+            NodeType::NumericLiteral(node) => node.span,
+            NodeType::IntegerLiteral(node) => node.span,
+            NodeType::BigIntLiteral(node) => node.span,
+            NodeType::Identifier(node) => node.span,
+            NodeType::StringLiteral(node) => node.span,
+            NodeType::TemplateLiteral(node) => node.span,
+            NodeType::BinaryExpr(node) => node.span,
+            NodeType::LogicalExpr(node) => node.span,
+            NodeType::UnaryExpr(node) => node.span,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Program {
     pub body: Vec<NodeType>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -34,6 +94,7 @@ pub struct VarDeclaration {
     pub constant: bool,
     pub identifier: String,
     pub value: Option<Box<NodeType>>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -41,6 +102,16 @@ pub struct FunctionDeclaration {
     pub parameters: Vec<String>,
     pub name: String,
     pub body: Vec<NodeType>,
+    pub span: Span,
+}
+
+/// An arrow function with an expression body, like `x => x * 2`, gets a body of a single
+/// `return` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionExpr {
+    pub parameters: Vec<String>,
+    pub body: Vec<NodeType>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -48,6 +119,13 @@ pub struct IfStatement {
     pub test: Box<NodeType>,
     pub body: Vec<NodeType>,
     pub alternate: Option<Vec<NodeType>>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockStatement {
+    pub body: Vec<NodeType>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -56,12 +134,50 @@ pub struct ForStatement {
     pub test: Box<NodeType>,
     pub update: Box<NodeType>,
     pub body: Vec<NodeType>,
+    /// Set for `label: for (...) { ... }`, so `break label` can target the loop.
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhileStatement {
+    pub test: Box<NodeType>,
+    pub body: Vec<NodeType>,
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoWhileStatement {
+    pub body: Vec<NodeType>,
+    pub test: Box<NodeType>,
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakStatement {
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContinueStatement {
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnStatement {
+    pub argument: Option<Box<NodeType>>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TryCatchStatement {
     pub body: Vec<NodeType>,
     pub alternate: Vec<NodeType>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -69,12 +185,29 @@ pub struct BinaryExpr {
     pub left: Box<NodeType>,
     pub right: Box<NodeType>,
     pub operator: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnaryExpr {
+    pub operator: String,
+    pub argument: Box<NodeType>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalExpr {
+    pub left: Box<NodeType>,
+    pub right: Box<NodeType>,
+    pub operator: String,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CallExpr {
     pub args: Vec<NodeType>,
     pub caller: Box<NodeType>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -82,36 +215,70 @@ pub struct MemberExpr {
     pub object: Box<NodeType>,
     pub property: Box<NodeType>,
     pub computed: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct AssignmentExpr {
     pub assign: Box<NodeType>,
     pub value: Box<NodeType>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Property {
     pub key: String,
     pub value: Option<Box<NodeType>>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ObjectLiteral {
     pub properties: Vec<NodeType>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayLiteral {
+    pub elements: Vec<NodeType>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct NumericLiteral {
-    pub value: f32,
+    pub value: f64,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegerLiteral {
+    pub value: i64,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BigIntLiteral {
+    pub value: BigInt,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Identifier {
     pub symbol: String,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StringLiteral {
     pub value: String,
+    pub span: Span,
+}
+
+/// A backtick string. The text parts (`quasis`) and `${}` holes (`expressions`) alternate,
+/// starting and ending with text, so there is always one more quasi than expressions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateLiteral {
+    pub quasis: Vec<String>,
+    pub expressions: Vec<NodeType>,
+    pub span: Span,
 }
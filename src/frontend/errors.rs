@@ -0,0 +1,216 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+use super::{lexer::TokenType, span::Span};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+    UnrecognizedCharacter {
+        character: char,
+        span: Span,
+    },
+    UnterminatedComment {
+        span: Span,
+    },
+    UnterminatedString {
+        span: Span,
+    },
+    InvalidEscape {
+        sequence: String,
+        span: Span,
+    },
+    InvalidNumber {
+        literal: String,
+        reason: String,
+        span: Span,
+    },
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        match self {
+            LexError::UnrecognizedCharacter { span, .. }
+            | LexError::UnterminatedComment { span }
+            | LexError::UnterminatedString { span }
+            | LexError::InvalidEscape { span, .. }
+            | LexError::InvalidNumber { span, .. } => *span,
+        }
+    }
+
+    /// The error message without any location information.
+    pub fn message(&self) -> String {
+        match self {
+            LexError::UnrecognizedCharacter { character, .. } => {
+                format!("Unrecognizable character found in sources: {}", character)
+            }
+            LexError::UnterminatedComment { .. } => {
+                "Block comment (\"/*\") is never closed.".to_string()
+            }
+            LexError::UnterminatedString { .. } => "Unterminated string literal.".to_string(),
+            LexError::InvalidEscape { sequence, .. } => {
+                format!("Invalid escape sequence in string: {}", sequence)
+            }
+            LexError::InvalidNumber {
+                literal, reason, ..
+            } => format!("Invalid number literal '{}': {}.", literal, reason),
+        }
+    }
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at {})", self.message(), self.span())
+    }
+}
+
+impl Error for LexError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Lex(LexError),
+    /// A specific token was required, but another one was found.
+    ExpectedToken {
+        expected: TokenType,
+        found: TokenType,
+        message: String,
+        span: Span,
+    },
+    /// The token can't start an expression.
+    UnexpectedToken {
+        found: TokenType,
+        value: String,
+        span: Span,
+    },
+    InvalidParameter {
+        span: Span,
+    },
+    MissingConstantValue {
+        identifier: String,
+        span: Span,
+    },
+    MissingCatch {
+        found: TokenType,
+        span: Span,
+    },
+    InvalidMemberProperty {
+        span: Span,
+    },
+    /// `break` or `continue` outside of a loop.
+    OutsideLoop {
+        keyword: String,
+        span: Span,
+    },
+    /// `return` outside of a function.
+    OutsideFunction {
+        span: Span,
+    },
+    /// `break`, `continue` or `return` inside a `try` used as a value, jumping out of it.
+    JumpOutOfTryExpr {
+        keyword: String,
+        span: Span,
+    },
+    /// `break label` or `continue label` without an enclosing loop of that name.
+    UnknownLabel {
+        label: String,
+        span: Span,
+    },
+    /// A label in front of something that isn't a loop.
+    InvalidLabel {
+        label: String,
+        span: Span,
+    },
+    /// The literal is well-formed, but its value can't be represented.
+    NumberOutOfRange {
+        literal: String,
+        span: Span,
+    },
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::Lex(lex_error) => lex_error.span(),
+            ParseError::ExpectedToken { span, .. }
+            | ParseError::UnexpectedToken { span, .. }
+            | ParseError::InvalidParameter { span }
+            | ParseError::MissingConstantValue { span, .. }
+            | ParseError::MissingCatch { span, .. }
+            | ParseError::InvalidMemberProperty { span }
+            | ParseError::OutsideLoop { span, .. }
+            | ParseError::OutsideFunction { span }
+            | ParseError::JumpOutOfTryExpr { span, .. }
+            | ParseError::UnknownLabel { span, .. }
+            | ParseError::InvalidLabel { span, .. }
+            | ParseError::NumberOutOfRange { span, .. } => *span,
+        }
+    }
+
+    /// The error message without any location information.
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::Lex(lex_error) => lex_error.message(),
+            ParseError::ExpectedToken { message, found, .. } => {
+                format!("{} Found {:?}.", message, found)
+            }
+            ParseError::UnexpectedToken { found, value, .. } => {
+                format!(
+                    "Unexpected token found during parsing! {:?} (\"{}\")",
+                    found, value
+                )
+            }
+            ParseError::InvalidParameter { .. } => {
+                "Arguments for \"fn\" statement must be identifiers.".to_string()
+            }
+            ParseError::MissingConstantValue { identifier, .. } => format!(
+                "Constant variables must have assigned values, but '{}' has none.",
+                identifier
+            ),
+            ParseError::MissingCatch { found, .. } => format!(
+                "\"try\" statement must be followed by a \"catch\" statement. Found {:?}.",
+                found
+            ),
+            ParseError::InvalidMemberProperty { .. } => {
+                "Dot operator (\".\") is illegal without right-hand-side (<-) being an Identifier."
+                    .to_string()
+            }
+            ParseError::OutsideLoop { keyword, .. } => {
+                format!("\"{}\" can only be used inside a loop.", keyword)
+            }
+            ParseError::OutsideFunction { .. } => {
+                "\"return\" can only be used inside a function.".to_string()
+            }
+            ParseError::JumpOutOfTryExpr { keyword, .. } => format!(
+                "\"{}\" can't be used inside a \"try\" that is used as a value.",
+                keyword
+            ),
+            ParseError::UnknownLabel { label, .. } => {
+                format!(
+                    "There is no loop labelled '{}' around this statement.",
+                    label
+                )
+            }
+            ParseError::InvalidLabel { label, .. } => {
+                format!("Label '{}' must be followed by a loop.", label)
+            }
+            ParseError::NumberOutOfRange { literal, .. } => {
+                format!("Number literal '{}' is too large.", literal)
+            }
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at {})", self.message(), self.span())
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<LexError> for ParseError {
+    fn from(err: LexError) -> Self {
+        ParseError::Lex(err)
+    }
+}
@@ -1,11 +1,18 @@
-use std::{collections::HashMap, process};
+use std::collections::HashMap;
+
+use super::{errors::LexError, span::Span};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     // Literal Types
     Number,
+    BigInt, // 123n
     Identifier,
     String,
+    Template,       // `text` without any holes
+    TemplateHead,   // `text${
+    TemplateMiddle, // }text${
+    TemplateTail,   // }text`
     // Keywords
     Let,
     Const,
@@ -13,10 +20,17 @@ pub enum TokenType {
     If,
     Else,
     For,
+    While,
+    Do,
+    Break,
+    Continue,
+    Return,
+    Typeof,
 
     // Grouping * Operators
     BinaryOperator,
     Equals,           // =
+    Arrow,            // =>
     Comma,            // ,
     Colon,            // :
     Semicolon,        // ;
@@ -29,25 +43,52 @@ pub enum TokenType {
     CloseBracket,     // ]
     Greater,          // >
     Lesser,           // <
+    GreaterEquals,    // >=
+    LesserEquals,     // <=
     EqualsCompare,    // ==
     NotEqualsCompare, // !=
     Exclamation,      // !
     And,              // &&
+    Or,               // ||
     Ampersand,        // &
     Bar,              // |
-    EOF,              // Signified the end of file.
+    Caret,            // ^
+    Eof,              // Signified the end of file.
 }
 
-const KEYWORDS: &[(&str, TokenType); 6] = &[
+const KEYWORDS: &[(&str, TokenType); 12] = &[
     ("let", TokenType::Let),
     ("const", TokenType::Const),
     ("fn", TokenType::Fn),
     ("if", TokenType::If),
     ("else", TokenType::Else),
     ("for", TokenType::For),
+    ("while", TokenType::While),
+    ("do", TokenType::Do),
+    ("break", TokenType::Break),
+    ("continue", TokenType::Continue),
+    ("return", TokenType::Return),
+    ("typeof", TokenType::Typeof),
+];
+
+// Operators longer than one character. These are matched before `TOKEN_CHARS`, so `<=` isn't
+// lexed as `<` followed by `=`.
+const OPERATORS: &[(&str, TokenType); 12] = &[
+    ("===", TokenType::EqualsCompare),
+    ("!==", TokenType::NotEqualsCompare),
+    ("=>", TokenType::Arrow),
+    ("==", TokenType::EqualsCompare),
+    ("!=", TokenType::NotEqualsCompare),
+    ("<=", TokenType::LesserEquals),
+    (">=", TokenType::GreaterEquals),
+    ("&&", TokenType::And),
+    ("||", TokenType::Or),
+    ("**", TokenType::BinaryOperator),
+    ("<<", TokenType::BinaryOperator),
+    (">>", TokenType::BinaryOperator),
 ];
 
-const TOKEN_CHARS: &[(char, TokenType); 18] = &[
+const TOKEN_CHARS: &[(char, TokenType); 22] = &[
     ('(', TokenType::OpenParen),
     (')', TokenType::CloseParen),
     ('{', TokenType::OpenBrace),
@@ -66,161 +107,771 @@ const TOKEN_CHARS: &[(char, TokenType); 18] = &[
     (':', TokenType::Colon),
     (',', TokenType::Comma),
     ('|', TokenType::Bar),
+    ('&', TokenType::Ampersand),
+    ('^', TokenType::Caret),
+    ('!', TokenType::Exclamation),
+    ('=', TokenType::Equals),
 ];
 
-#[derive(Debug)]
+/// A `// line` or `/* block */` comment. `text` is everything between the delimiters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub text: String,
+    pub block: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
 pub struct Token {
     pub value: String,
     pub tok_type: TokenType,
+    pub span: Span,
+    /// Comments between the previous token and this one, kept around for tooling.
+    pub comments: Vec<Comment>,
 }
 
-fn token(value: Option<&str>, tok_type: TokenType) -> Token {
+fn token(value: Option<&str>, tok_type: TokenType, span: Span) -> Token {
     Token {
-        value: value.map_or_else(|| String::new(), String::from),
+        value: value.map_or_else(String::new, String::from),
         tok_type,
+        span,
+        comments: vec![],
     }
 }
 
-fn is_alpha(src: &str, is_first_char: bool) -> bool {
+fn is_alpha(c: char, is_first_char: bool) -> bool {
     if is_first_char {
-        return src
-            .chars()
-            .next()
-            .map_or(false, |c| c.is_ascii_alphabetic() || c == '_');
+        return c.is_ascii_alphabetic() || c == '_';
     }
 
-    src.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    c.is_ascii_alphanumeric() || c == '_'
 }
 
-fn is_skippable(str: String) -> bool {
-    str == " " || str == "\n" || str == "\t" || str == "\r"
+fn is_skippable(c: char) -> bool {
+    c == ' ' || c == '\n' || c == '\t' || c == '\r'
 }
 
-fn is_int(str: &String) -> bool {
-    str.chars().all(|c| c.is_digit(10))
+fn is_int(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_ascii_digit())
 }
 
-fn str_to_first_char(str: &String) -> char {
-    str.chars()
-        .next()
-        .expect("Failed to convert &str to first char")
+/// Checks the digits of a number literal (everything after the sign and radix prefix) and
+/// strips its `_` separators.
+fn validate_number(digits: &str, radix: u32) -> Result<String, &'static str> {
+    let chars: Vec<char> = digits.chars().collect();
+    let is_digit = |c: Option<&char>| c.is_some_and(|c| c.is_digit(radix));
+
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' && (i == 0 || !is_digit(chars.get(i - 1)) || !is_digit(chars.get(i + 1))) {
+            return Err("\"_\" is only allowed between digits");
+        }
+    }
+
+    let digits: String = chars.into_iter().filter(|&c| c != '_').collect();
+
+    if radix != 10 {
+        if digits.is_empty() {
+            return Err("expected digits after the prefix");
+        }
+
+        if !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(match radix {
+                16 => "hexadecimal literals can only contain 0-9 and a-f",
+                8 => "octal literals can only contain 0-7",
+                _ => "binary literals can only contain 0 and 1",
+            });
+        }
+
+        return Ok(digits);
+    }
+
+    let (mantissa, exponent) = match digits.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (digits.as_str(), None),
+    };
+
+    if !mantissa.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err("unexpected character in number");
+    }
+
+    if let Some(exponent) = exponent {
+        let exponent_digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+
+        if exponent_digits.is_empty() || !exponent_digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err("expected digits in the exponent");
+        }
+    }
+
+    Ok(digits)
 }
 
-fn first_char_as_str(str: &Vec<String>) -> String {
-    str.get(0).expect("Failed to get char at pos 0").clone()
+/// Walks over the source code one character at a time while keeping track of
+/// where it is, so every token can be given a `Span`. Errors are collected in `errors`
+/// instead of stopping it, so a single bad literal doesn't hide everything after it.
+struct Lexer {
+    src: Vec<char>,
+    pos: usize,
+    offset: usize,
+    line: usize,
+    column: usize,
+    errors: Vec<LexError>,
 }
 
-pub fn tokenize(source_code: String) -> Vec<Token> {
-    let token_chars_map: HashMap<char, TokenType> = TOKEN_CHARS.iter().cloned().collect();
-    let keywords_map: HashMap<&str, TokenType> = KEYWORDS.iter().cloned().collect();
+impl Lexer {
+    fn new(source_code: &str) -> Self {
+        Lexer {
+            src: source_code.chars().collect(),
+            pos: 0,
+            offset: 0,
+            line: 1,
+            column: 1,
+            errors: vec![],
+        }
+    }
 
-    let mut tokens: Vec<Token> = vec![];
-    let mut src: Vec<String> = source_code
-        .split("")
-        .flat_map(|s| s.chars().map(|c| c.to_string()))
-        .collect();
-
-    while src.len() > 0 {
-        let src_char_as_str = first_char_as_str(&src);
-        let c = str_to_first_char(&src_char_as_str);
-
-        // can you understand this? me neither, but it works.
-        if is_int(&c.to_string())
-            || (c == '-' && is_int(src.get(1).expect("Failed to get char at pos 1")))
-        {
-            let mut num = src.remove(0);
-            let mut period = false;
-
-            'num_loop: while src.len() > 0
-                && (is_int(&str_to_first_char(&first_char_as_str(&src)).to_string())
-                    || str_to_first_char(&first_char_as_str(&src)) == '.')
-            {
-                if str_to_first_char(&first_char_as_str(&src)) == '.' && !period {
-                    period = true;
-                    num += src.remove(0).as_str();
-                } else if is_int(&str_to_first_char(&first_char_as_str(&src)).to_string()) {
-                    num += src.remove(0).as_str();
+    fn peek(&self, n: usize) -> Option<char> {
+        self.src.get(self.pos + n).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+
+        self.pos += 1;
+        self.offset += c.len_utf8();
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    /// Whether the source code continues with `text`.
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek(i) == Some(c))
+    }
+
+    /// An empty span at the current position, to be closed with `span_from`.
+    fn mark(&self) -> Span {
+        Span::new(self.offset, self.offset, self.line, self.column)
+    }
+
+    fn span_from(&self, start: Span) -> Span {
+        Span {
+            end: self.offset,
+            ..start
+        }
+    }
+
+    /// Lexes a number literal: decimal with an optional fraction and exponent (`1.5e-3`), or
+    /// an integer with a `0x`, `0b` or `0o` prefix. Digits can be separated with `_`.
+    /// Returns the literal without separators, as a `BigInt` token if it ends in `n`. An
+    /// invalid literal is recorded and lexed as `0`, so parsing can carry on around it.
+    fn number(&mut self) -> (String, TokenType) {
+        let start = self.mark();
+        let mut literal = String::new();
+
+        let radix = match (self.peek(0), self.peek(1).map(|c| c.to_ascii_lowercase())) {
+            (Some('0'), Some('x')) => 16,
+            (Some('0'), Some('b')) => 2,
+            (Some('0'), Some('o')) => 8,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            literal.extend(self.advance());
+            literal.extend(self.advance());
+        }
+
+        let prefix_len = literal.len();
+        let mut period = false;
+
+        // Anything that looks like it belongs to the literal is eaten first and validated
+        // afterwards, so `0b102` is one bad literal rather than `0b10` followed by `2`.
+        while let Some(c) = self.peek(0) {
+            let exponent_sign =
+                radix == 10 && (c == '+' || c == '-') && literal.ends_with(['e', 'E']);
+
+            if c == '.' && radix == 10 && !period && !literal.contains(['e', 'E']) {
+                period = true;
+            } else if !is_alpha(c, false) && !exponent_sign {
+                break;
+            }
+
+            literal.push(c);
+            self.advance();
+        }
+
+        let bigint = literal.len() > prefix_len && literal.ends_with('n');
+        let digits = &literal[prefix_len..literal.len() - bigint as usize];
+
+        let validated = if bigint && radix == 10 && digits.contains(['.', 'e', 'E']) {
+            Err("BigInt literals must be whole numbers")
+        } else {
+            validate_number(digits, radix)
+        };
+
+        match validated {
+            Ok(digits) => (
+                format!("{}{}", &literal[..prefix_len], digits),
+                if bigint {
+                    TokenType::BigInt
                 } else {
-                    break 'num_loop;
+                    TokenType::Number
+                },
+            ),
+            Err(reason) => {
+                self.errors.push(LexError::InvalidNumber {
+                    literal,
+                    reason: reason.to_string(),
+                    span: self.span_from(start),
+                });
+
+                ("0".to_string(), TokenType::Number)
+            }
+        }
+    }
+
+    /// Lexes a string literal delimited by `quote`, resolving escape sequences. An
+    /// unterminated string runs to the end of the file.
+    fn string(&mut self, quote: char) -> String {
+        let start = self.mark();
+        let mut str = String::new();
+
+        self.advance();
+
+        loop {
+            match self.advance() {
+                Some(c) if c == quote => return str,
+                Some('\\') => self.push_escape_sequence(&mut str),
+                Some(c) => str.push(c),
+                None => {
+                    self.unterminated_string(start);
+                    return str;
                 }
             }
+        }
+    }
 
-            tokens.push(token(Some(num.as_str()), TokenType::Number));
-        } else if let Some(token_type) = token_chars_map.get(&c) {
-            tokens.push(token(Some(src.remove(0).as_str()), token_type.clone()));
-        } else {
-            match c {
-                '=' => {
-                    src.remove(0);
+    fn unterminated_string(&mut self, start: Span) {
+        self.errors.push(LexError::UnterminatedString {
+            span: Span {
+                end: start.start + 1,
+                ..start
+            },
+        });
+    }
 
-                    if first_char_as_str(&src) == "=" {
-                        src.remove(0);
-                        tokens.push(token(Some("=="), TokenType::EqualsCompare));
-                    } else {
-                        tokens.push(token(Some("="), TokenType::Equals));
-                    }
+    /// Lexes template text up to and including the closing backtick or the `${` of the
+    /// next hole. Returns the text, and whether a hole follows it.
+    fn template_chunk(&mut self, start: Span) -> (String, bool) {
+        let mut str = String::new();
+
+        loop {
+            match self.advance() {
+                Some('`') => return (str, false),
+                Some('$') if self.peek(0) == Some('{') => {
+                    self.advance();
+                    return (str, true);
                 }
-                '&' => {
-                    src.remove(0);
+                Some('\\') => self.push_escape_sequence(&mut str),
+                Some(c) => str.push(c),
+                None => {
+                    self.unterminated_string(start);
+                    return (str, false);
+                }
+            }
+        }
+    }
 
-                    if first_char_as_str(&src) == "&" {
-                        src.remove(0);
-                        tokens.push(token(Some("&&"), TokenType::And));
-                    } else {
-                        tokens.push(token(Some("&"), TokenType::Ampersand));
-                    }
+    /// Adds the character an escape sequence stands for to `str`. An invalid one is
+    /// recorded and left out.
+    fn push_escape_sequence(&mut self, str: &mut String) {
+        match self.escape_sequence() {
+            Ok(c) => str.push(c),
+            Err(err) => self.errors.push(err),
+        }
+    }
+
+    /// Resolves the escape sequence following a backslash that was just eaten.
+    fn escape_sequence(&mut self) -> Result<char, LexError> {
+        let start = Span {
+            start: self.offset - 1,
+            column: self.column - 1,
+            ..self.mark()
+        };
+
+        let escaped = match self.advance() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some(c @ ('"' | '\'' | '\\' | '`' | '$')) => c,
+            Some('u') if self.peek(0) == Some('{') => {
+                self.advance();
+
+                let mut hex = String::new();
+
+                while let Some(c) = self.peek(0).filter(|c| c.is_ascii_hexdigit()) {
+                    hex.push(c);
+                    self.advance();
                 }
-                '!' => {
-                    src.remove(0);
 
-                    if first_char_as_str(&src) == "=" {
-                        src.remove(0);
-                        tokens.push(token(Some("!="), TokenType::NotEqualsCompare));
-                    } else {
-                        tokens.push(token(Some("!"), TokenType::Exclamation));
+                let closed = self.peek(0) == Some('}');
+
+                if closed {
+                    self.advance();
+                }
+
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(c) if closed && hex.len() <= 6 => c,
+                    _ => {
+                        return Err(LexError::InvalidEscape {
+                            sequence: format!("\\u{{{}{}", hex, if closed { "}" } else { "" }),
+                            span: self.span_from(start),
+                        })
                     }
                 }
-                '"' => {
-                    let mut str = String::new();
-                    src.remove(0);
+            }
+            Some(c) => {
+                return Err(LexError::InvalidEscape {
+                    sequence: format!("\\{}", c),
+                    span: self.span_from(start),
+                })
+            }
+            None => {
+                return Err(LexError::InvalidEscape {
+                    sequence: "\\".to_string(),
+                    span: self.span_from(start),
+                })
+            }
+        };
+
+        Ok(escaped)
+    }
+
+    /// Lexes a comment if one starts at the current position. An unclosed block comment
+    /// runs to the end of the file.
+    fn comment(&mut self) -> Option<Comment> {
+        let start = self.mark();
+        let mut text = String::new();
+
+        match (self.peek(0), self.peek(1)) {
+            (Some('/'), Some('/')) => {
+                self.advance();
+                self.advance();
+
+                while let Some(c) = self.peek(0).filter(|&c| c != '\n') {
+                    text.push(c);
+                    self.advance();
+                }
 
-                    while src.len() > 0 && first_char_as_str(&src) != "\"" {
-                        str += src.remove(0).as_str();
+                Some(Comment {
+                    text,
+                    block: false,
+                    span: self.span_from(start),
+                })
+            }
+            (Some('/'), Some('*')) => {
+                self.advance();
+                self.advance();
+
+                // Block comments nest, so `/* a /* b */ c */` is a single comment.
+                let mut depth = 1;
+
+                loop {
+                    match (self.peek(0), self.peek(1)) {
+                        (Some('/'), Some('*')) => depth += 1,
+                        (Some('*'), Some('/')) => depth -= 1,
+                        (Some(_), _) => {
+                            text.extend(self.advance());
+                            continue;
+                        }
+                        (None, _) => {
+                            self.errors.push(LexError::UnterminatedComment {
+                                span: Span {
+                                    end: start.start + 2,
+                                    ..start
+                                },
+                            });
+                            break;
+                        }
+                    }
+
+                    if depth == 0 {
+                        self.advance();
+                        self.advance();
+                        break;
                     }
 
-                    src.remove(0);
-                    tokens.push(token(Some(str.as_str()), TokenType::String));
+                    text.extend(self.advance());
+                    text.extend(self.advance());
+                }
+
+                Some(Comment {
+                    text,
+                    block: true,
+                    span: self.span_from(start),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Splits the source code into tokens. Lexing doesn't stop at an error: whatever caused it
+/// is skipped, and every error is returned next to the tokens.
+pub fn tokenize(source_code: String) -> (Vec<Token>, Vec<LexError>) {
+    let token_chars_map: HashMap<char, TokenType> = TOKEN_CHARS.iter().cloned().collect();
+    let keywords_map: HashMap<&str, TokenType> = KEYWORDS.iter().cloned().collect();
+
+    let mut tokens: Vec<Token> = vec![];
+    let mut comments: Vec<Comment> = vec![];
+    let mut lexer = Lexer::new(&source_code);
+    // Unclosed braces inside each template hole we're in, innermost last. A `}` closes
+    // the hole once its count is back at 0.
+    let mut template_braces: Vec<usize> = vec![];
+    // Where the last token ends. The end of the file is reported there, rather than on the
+    // empty line after a trailing newline.
+    let mut end_of_tokens = lexer.mark();
+
+    while let Some(c) = lexer.peek(0) {
+        let start = lexer.mark();
+        let tokens_len = tokens.len();
+
+        if let Some(comment) = lexer.comment() {
+            comments.push(comment);
+            continue;
+        }
+
+        if is_int(Some(c)) {
+            let (num, tok_type) = lexer.number();
+
+            tokens.push(token(Some(num.as_str()), tok_type, lexer.span_from(start)));
+        } else if c == '`' || (c == '}' && template_braces.last() == Some(&0)) {
+            lexer.advance();
+
+            let (str, hole) = lexer.template_chunk(start);
+            let tok_type = match (c, hole) {
+                ('`', false) => TokenType::Template,
+                ('`', true) => TokenType::TemplateHead,
+                (_, true) => TokenType::TemplateMiddle,
+                (_, false) => TokenType::TemplateTail,
+            };
+
+            if c == '`' && hole {
+                template_braces.push(0);
+            } else if c == '}' && !hole {
+                template_braces.pop();
+            }
+
+            tokens.push(token(Some(str.as_str()), tok_type, lexer.span_from(start)));
+        } else if let Some((operator, token_type)) = OPERATORS
+            .iter()
+            .find(|(operator, _)| lexer.starts_with(operator))
+        {
+            for _ in 0..operator.len() {
+                lexer.advance();
+            }
+
+            tokens.push(token(
+                Some(operator),
+                token_type.clone(),
+                lexer.span_from(start),
+            ));
+        } else if let Some(token_type) = token_chars_map.get(&c) {
+            if let Some(braces) = template_braces.last_mut() {
+                match c {
+                    '{' => *braces += 1,
+                    '}' => *braces -= 1,
+                    _ => {}
+                }
+            }
+
+            lexer.advance();
+            tokens.push(token(
+                Some(c.to_string().as_str()),
+                token_type.clone(),
+                lexer.span_from(start),
+            ));
+        } else {
+            match c {
+                '"' | '\'' => {
+                    let str = lexer.string(c);
+
+                    tokens.push(token(
+                        Some(str.as_str()),
+                        TokenType::String,
+                        lexer.span_from(start),
+                    ));
                 }
                 _ => {
-                    if is_alpha(c.to_string().as_str(), true) {
+                    if is_alpha(c, true) {
                         let mut ident = String::new();
-                        ident += src.remove(0).as_str();
 
-                        while src.len() > 0 && is_alpha(&first_char_as_str(&src), false) {
-                            ident += src.remove(0).as_str();
+                        while let Some(next) = lexer.peek(0).filter(|&next| is_alpha(next, false)) {
+                            ident.push(next);
+                            lexer.advance();
                         }
 
+                        let span = lexer.span_from(start);
+
                         if let Some(reserved) = keywords_map.get(&ident.as_str()) {
-                            tokens.push(token(Some(ident.as_str()), reserved.clone()));
+                            tokens.push(token(Some(ident.as_str()), reserved.clone(), span));
                         } else {
-                            tokens.push(token(Some(ident.as_str()), TokenType::Identifier));
+                            tokens.push(token(Some(ident.as_str()), TokenType::Identifier, span));
                         }
-                    } else if is_skippable(first_char_as_str(&src)) {
-                        src.remove(0);
+                    } else if is_skippable(c) {
+                        lexer.advance();
                     } else {
-                        println!(
-                            "Unrecognizable character found in sources: {}",
-                            first_char_as_str(&src)
-                        );
-
-                        process::exit(1);
+                        lexer.advance();
+                        lexer.errors.push(LexError::UnrecognizedCharacter {
+                            character: c,
+                            span: lexer.span_from(start),
+                        });
                     }
                 }
             }
         }
+
+        if tokens.len() > tokens_len {
+            if let Some(last) = tokens.last_mut() {
+                last.comments = std::mem::take(&mut comments);
+            }
+
+            end_of_tokens = lexer.mark();
+        }
+    }
+
+    let mut eof = token(Some("EndOfFile"), TokenType::Eof, end_of_tokens);
+    eof.comments = comments;
+    tokens.push(eof);
+
+    (tokens, lexer.errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(source: &str) -> (Vec<(TokenType, String)>, Vec<LexError>) {
+        let (tokens, errors) = tokenize(source.to_string());
+        let tokens = tokens
+            .into_iter()
+            .filter(|token| token.tok_type != TokenType::Eof)
+            .map(|token| (token.tok_type, token.value))
+            .collect();
+
+        (tokens, errors)
     }
 
-    tokens.push(token(Some("EndOfFile"), TokenType::EOF));
+    /// Lexes a single literal that has to be valid.
+    fn literal(source: &str) -> (TokenType, String) {
+        let (mut tokens, errors) = lex(source);
 
-    tokens
+        assert_eq!(errors, vec![], "{} should be valid", source);
+        assert_eq!(tokens.len(), 1, "{} should be a single token", source);
+
+        tokens.remove(0)
+    }
+
+    /// Lexes a single literal that has to be invalid, and returns its one error.
+    fn invalid(source: &str) -> LexError {
+        let (_, mut errors) = lex(source);
+
+        assert_eq!(errors.len(), 1, "{} should have one error", source);
+
+        errors.remove(0)
+    }
+
+    fn number(value: &str) -> (TokenType, String) {
+        (TokenType::Number, value.to_string())
+    }
+
+    fn invalid_number(source: &str) -> String {
+        match invalid(source) {
+            LexError::InvalidNumber {
+                literal, reason, ..
+            } => {
+                assert_eq!(literal, source);
+                reason
+            }
+            err => panic!("{} should be an invalid number, got {:?}", source, err),
+        }
+    }
+
+    #[test]
+    fn valid_numbers() {
+        assert_eq!(literal("42"), number("42"));
+        assert_eq!(literal("1_000_000"), number("1000000"));
+        assert_eq!(literal("1.5"), number("1.5"));
+        assert_eq!(literal("1.5e-3"), number("1.5e-3"));
+        assert_eq!(literal("2E+10"), number("2E+10"));
+        assert_eq!(literal("0xff_ff"), number("0xffff"));
+        assert_eq!(literal("0XAB"), number("0XAB"));
+        assert_eq!(literal("0o17"), number("0o17"));
+        assert_eq!(literal("0b1010"), number("0b1010"));
+        assert_eq!(literal("123n"), (TokenType::BigInt, "123".to_string()));
+        assert_eq!(literal("0xffn"), (TokenType::BigInt, "0xff".to_string()));
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        assert_eq!(invalid_number("0x"), "expected digits after the prefix");
+        assert_eq!(
+            invalid_number("1__0"),
+            "\"_\" is only allowed between digits"
+        );
+        assert_eq!(invalid_number("1_"), "\"_\" is only allowed between digits");
+        assert_eq!(
+            invalid_number("0x_1"),
+            "\"_\" is only allowed between digits"
+        );
+        assert_eq!(
+            invalid_number("0b102"),
+            "binary literals can only contain 0 and 1"
+        );
+        assert_eq!(invalid_number("0o8"), "octal literals can only contain 0-7");
+        assert_eq!(
+            invalid_number("0xfg"),
+            "hexadecimal literals can only contain 0-9 and a-f"
+        );
+        assert_eq!(invalid_number("1e"), "expected digits in the exponent");
+        assert_eq!(invalid_number("1e+"), "expected digits in the exponent");
+        assert_eq!(invalid_number("12abc"), "unexpected character in number");
+        assert_eq!(
+            invalid_number("1.5n"),
+            "BigInt literals must be whole numbers"
+        );
+    }
+
+    #[test]
+    fn invalid_number_is_skipped() {
+        let (tokens, errors) = lex("f(0x, 1)");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<_>>(),
+            ["f", "(", "0", ",", "1", ")"]
+        );
+    }
+
+    #[test]
+    fn escape_sequences() {
+        let string = |value: &str| (TokenType::String, value.to_string());
+
+        assert_eq!(literal(r#""a\nb\tc""#), string("a\nb\tc"));
+        assert_eq!(literal(r#"'\'\"\\\0'"#), string("'\"\\\0"));
+        assert_eq!(literal(r#""\u{41}\u{1F600}""#), string("A\u{1F600}"));
+        assert_eq!(
+            literal(r"`\`\${}`"),
+            (TokenType::Template, "`${}".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_escape_sequences() {
+        for (source, sequence) in [
+            (r#""\u{110000}""#, r"\u{110000}"),
+            (r#""\u{1234567}""#, r"\u{1234567}"),
+            (r#""\u{41""#, r"\u{41"),
+            (r#""\q""#, r"\q"),
+        ] {
+            match invalid(source) {
+                LexError::InvalidEscape {
+                    sequence: found,
+                    span,
+                } => {
+                    assert_eq!(found, sequence);
+                    assert_eq!(span.start, 1);
+                }
+                err => panic!("{} should be an invalid escape, got {:?}", source, err),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_escape_is_left_out() {
+        let (tokens, errors) = lex(r#""a\qb" 1"#);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            tokens,
+            [
+                (TokenType::String, "ab".to_string()),
+                (TokenType::Number, "1".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_strings() {
+        for source in ["let s = \"abc", "let s = 'abc\n", "let s = `abc"] {
+            match invalid(source) {
+                LexError::UnterminatedString { span } => {
+                    assert_eq!((span.start, span.end), (8, 9), "{}", source);
+                    assert_eq!((span.line, span.column), (1, 9), "{}", source);
+                }
+                err => panic!("{} should be unterminated, got {:?}", source, err),
+            }
+        }
+    }
+
+    #[test]
+    fn nested_block_comments() {
+        let (tokens, errors) = lex("1 /* a /* b */ still a comment */ 2");
+
+        assert_eq!(errors, vec![]);
+        assert_eq!(tokens, [number("1"), number("2")]);
+    }
+
+    #[test]
+    fn unterminated_comments() {
+        for source in ["1 /* never closed", "1 /* a /* b */ c"] {
+            match invalid(source) {
+                LexError::UnterminatedComment { span } => {
+                    assert_eq!((span.start, span.end), (2, 4), "{}", source);
+                }
+                err => panic!(
+                    "{} should be an unterminated comment, got {:?}",
+                    source, err
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn comments_are_kept_on_the_next_token() {
+        let (tokens, _) = tokenize("// first\nlet /* second */ x\n// trailing".to_string());
+        let comments = |index: usize| {
+            tokens[index]
+                .comments
+                .iter()
+                .map(|comment| (comment.text.as_str(), comment.block))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(comments(0), [(" first", false)]);
+        assert_eq!(comments(1), [(" second ", true)]);
+        assert_eq!(tokens[2].tok_type, TokenType::Eof);
+        assert_eq!(comments(2), [(" trailing", false)]);
+        assert_eq!(tokens[1].comments[0].span.start, 13);
+    }
+
+    #[test]
+    fn end_of_file_follows_last_token() {
+        let (tokens, _) = tokenize("println(1\n\n".to_string());
+        let eof = tokens.last().unwrap();
+
+        assert_eq!(eof.tok_type, TokenType::Eof);
+        assert_eq!((eof.span.start, eof.span.line, eof.span.column), (9, 1, 10));
+    }
 }
@@ -1,145 +1,659 @@
-use std::process;
+use num_bigint::BigInt;
 
 use super::{
     ast::{
-        AssignmentExpr, BinaryExpr, CallExpr, ForStatement, FunctionDeclaration, Identifier,
-        IfStatement, MemberExpr, NodeType, NumericLiteral, ObjectLiteral, Program, Property,
-        StringLiteral, TryCatchStatement, VarDeclaration,
+        ArrayLiteral, AssignmentExpr, BigIntLiteral, BinaryExpr, BlockStatement, BreakStatement,
+        CallExpr, ContinueStatement, DoWhileStatement, ForStatement, FunctionDeclaration,
+        FunctionExpr, Identifier, IfStatement, IntegerLiteral, LogicalExpr, MemberExpr, NodeType,
+        NumericLiteral, ObjectLiteral, Program, Property, ReturnStatement, StringLiteral,
+        TemplateLiteral, TryCatchStatement, UnaryExpr, VarDeclaration, WhileStatement,
     },
+    errors::ParseError,
     lexer::{tokenize, Token, TokenType},
+    span::Span,
 };
 
+/// Converts a number literal, as validated by the lexer, into an integer literal if it's a
+/// whole number, or a float literal if it has a fraction or an exponent. A whole number that
+/// doesn't fit in an integer is out of range rather than quietly losing precision as a float,
+/// whatever its radix. Those need to be BigInts.
+fn parse_number_literal(literal: &str, span: Span) -> Option<NodeType> {
+    let (negative, digits) = match literal.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, literal),
+    };
+
+    let radix = match digits.get(..2).map(|prefix| prefix.to_ascii_lowercase()) {
+        Some(prefix) if prefix == "0x" => 16,
+        Some(prefix) if prefix == "0b" => 2,
+        Some(prefix) if prefix == "0o" => 8,
+        _ => 10,
+    };
+
+    if radix != 10 {
+        let magnitude = u64::from_str_radix(&digits[2..], radix).ok()? as i128;
+        let value = i64::try_from(if negative { -magnitude } else { magnitude }).ok()?;
+
+        return Some(NodeType::IntegerLiteral(IntegerLiteral { value, span }));
+    }
+
+    if !digits.contains(['.', 'e', 'E']) {
+        let value = literal.parse::<i64>().ok()?;
+
+        return Some(NodeType::IntegerLiteral(IntegerLiteral { value, span }));
+    }
+
+    let value = literal.parse::<f64>().ok()?;
+
+    if value.is_infinite() {
+        return None;
+    }
+
+    Some(NodeType::NumericLiteral(NumericLiteral { value, span }))
+}
+
+/// Turns a `Number` or `BigInt` token into a literal node. `negative` puts a minus sign in
+/// front of it, so a negated literal is parsed in one go and `-9223372036854775808` still fits
+/// in an integer.
+fn parse_number_token(token: Token, negative: bool, span: Span) -> Result<NodeType, ParseError> {
+    let literal = if negative {
+        format!("-{}", token.value)
+    } else {
+        token.value
+    };
+
+    if token.tok_type == TokenType::BigInt {
+        return Ok(NodeType::BigIntLiteral(BigIntLiteral {
+            value: parse_bigint_literal(&literal),
+            span,
+        }));
+    }
+
+    parse_number_literal(&literal, span).ok_or(ParseError::NumberOutOfRange { literal, span })
+}
+
+/// Converts a BigInt literal, without its `n` suffix, into its value.
+fn parse_bigint_literal(literal: &str) -> BigInt {
+    let (negative, digits) = match literal.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, literal),
+    };
+
+    let (radix, digits) = match digits.get(..2).map(|prefix| prefix.to_ascii_lowercase()) {
+        Some(prefix) if prefix == "0x" => (16, &digits[2..]),
+        Some(prefix) if prefix == "0b" => (2, &digits[2..]),
+        Some(prefix) if prefix == "0o" => (8, &digits[2..]),
+        _ => (10, digits),
+    };
+
+    // The lexer has already made sure these are valid digits.
+    let magnitude = BigInt::parse_bytes(digits.as_bytes(), radix).unwrap_or_default();
+
+    if negative {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+// Binary operators and how tightly they bind, from loosest to tightest. All of them are
+// left-associative except for `**`, so `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+// `**` isn't in here: it binds tighter than prefix operators, so it's parsed on its own by
+// `parse_exponent_expr`.
+const BINARY_OPERATORS: &[(&str, u8); 20] = &[
+    ("||", 1),
+    ("&&", 2),
+    ("|", 3),
+    ("^", 4),
+    ("&", 5),
+    ("==", 6),
+    ("!=", 6),
+    ("===", 6),
+    ("!==", 6),
+    ("<", 7),
+    (">", 7),
+    ("<=", 7),
+    (">=", 7),
+    ("<<", 8),
+    (">>", 8),
+    ("+", 9),
+    ("-", 9),
+    ("*", 10),
+    ("/", 10),
+    ("%", 10),
+];
+
+/// The precedence of the token if it's a binary operator.
+fn binary_precedence(token: &Token) -> Option<u8> {
+    if !matches!(
+        token.tok_type,
+        TokenType::BinaryOperator
+            | TokenType::Greater
+            | TokenType::Lesser
+            | TokenType::GreaterEquals
+            | TokenType::LesserEquals
+            | TokenType::EqualsCompare
+            | TokenType::NotEqualsCompare
+            | TokenType::And
+            | TokenType::Or
+            | TokenType::Ampersand
+            | TokenType::Bar
+            | TokenType::Caret
+    ) {
+        return None;
+    }
+
+    BINARY_OPERATORS
+        .iter()
+        .find(|(operator, _)| *operator == token.value)
+        .map(|(_, precedence)| *precedence)
+}
+
 pub struct Parser {
     tokens: Vec<Token>,
+    /// Span of the most recently eaten token, used to close the span of the node being parsed.
+    prev_span: Span,
+    /// Errors the parser has recovered from so far.
+    errors: Vec<ParseError>,
+    /// Labels of the loops around the statement being parsed, innermost last, so `break` and
+    /// `continue` can be checked. Function bodies start out with no loops around them.
+    loops: Vec<Option<String>>,
+    /// Whether the statement being parsed is inside a function, where `return` is allowed.
+    in_function: bool,
+    /// Whether the statement being parsed is inside a `try` used as a value. A value can't
+    /// carry a `break`, `continue` or `return` out with it, so those are only allowed if
+    /// whatever they jump out of is inside the `try` too.
+    in_try_expr: bool,
 }
 
 pub trait Parse {
     fn new() -> Self;
-    fn create_ast(&mut self, input: String) -> NodeType;
+    /// Parses the whole input, returning every error found if there were any.
+    fn create_ast(&mut self, input: String) -> Result<NodeType, Vec<ParseError>>;
+    /// Parses the whole input without stopping at errors. Statements that failed to parse are
+    /// left out of the returned program, and the errors are returned next to it.
+    fn parse_recovering(&mut self, input: String) -> (NodeType, Vec<ParseError>);
 }
 
 impl Parse for Parser {
     fn new() -> Self {
-        Parser { tokens: vec![] }
+        Parser {
+            tokens: vec![],
+            prev_span: Span::default(),
+            errors: vec![],
+            loops: vec![],
+            in_function: false,
+            in_try_expr: false,
+        }
+    }
+
+    fn create_ast(&mut self, input: String) -> Result<NodeType, Vec<ParseError>> {
+        let (program, errors) = self.parse_recovering(input);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(program)
     }
 
-    fn create_ast(&mut self, input: String) -> NodeType {
-        self.tokens = tokenize(input);
+    fn parse_recovering(&mut self, input: String) -> (NodeType, Vec<ParseError>) {
+        self.errors = vec![];
+        self.loops = vec![];
+        self.in_function = false;
+        self.in_try_expr = false;
 
-        let mut program = Program { body: vec![] };
+        let (tokens, lex_errors) = tokenize(input);
+
+        self.tokens = tokens;
+
+        let mut program = Program {
+            body: vec![],
+            span: self.at().span,
+        };
 
         while self.not_eof() {
-            program.body.push(self.parse_stmt());
+            if let Some(stmt) = self.parse_stmt_recovering(false) {
+                program.body.push(stmt);
+            }
         }
 
-        NodeType::Program(program)
+        program.span = program.span.to(self.prev_span);
+
+        let mut errors: Vec<ParseError> = lex_errors.into_iter().map(ParseError::from).collect();
+
+        errors.append(&mut self.errors);
+        errors.sort_by_key(|err| err.span().start);
+
+        (NodeType::Program(program), errors)
     }
 }
 
 impl Parser {
     fn not_eof(&self) -> bool {
-        self.tokens
-            .get(0)
-            .expect("Failed to get token at pos 0")
-            .tok_type
-            != TokenType::EOF
+        self.at().tok_type != TokenType::Eof
     }
 
     fn at(&self) -> &Token {
-        self.tokens.get(0).expect("Failed to get token at pos 0")
+        self.tokens.first().expect("Failed to get token at pos 0")
     }
 
     fn eat(&mut self) -> Token {
-        self.tokens.remove(0)
+        let token = self.tokens.remove(0);
+        self.prev_span = token.span;
+
+        token
+    }
+
+    /// Span from `start` up to the end of the last eaten token.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.prev_span)
+    }
+
+    /// Eats the current token if it is of type `tok_type`, otherwise leaves it in place and
+    /// returns an error built from `err`.
+    fn expect(&mut self, tok_type: TokenType, err: &str) -> Result<Token, ParseError> {
+        let current = self.at();
+
+        if current.tok_type != tok_type {
+            return Err(ParseError::ExpectedToken {
+                expected: tok_type,
+                found: current.tok_type.clone(),
+                message: err.to_string(),
+                span: current.span,
+            });
+        }
+
+        Ok(self.eat())
+    }
+
+    /// Whether the current token ends the statement before it, following the automatic
+    /// semicolon rule: a statement ends at a `;`, a `}`, the end of the file, or a token on a
+    /// later line than the one before it. So `;` is only needed between two statements on the
+    /// same line.
+    fn at_statement_end(&self) -> bool {
+        matches!(
+            self.at().tok_type,
+            TokenType::Semicolon | TokenType::CloseBrace | TokenType::Eof
+        ) || self.at().span.line > self.prev_span.line
+    }
+
+    /// Ends a statement according to `at_statement_end`, eating its `;` if it has one.
+    fn end_statement(&mut self, err: &str) -> Result<(), ParseError> {
+        if !self.at_statement_end() {
+            return Err(ParseError::ExpectedToken {
+                expected: TokenType::Semicolon,
+                found: self.at().tok_type.clone(),
+                message: err.to_string(),
+                span: self.at().span,
+            });
+        }
+
+        if matches!(self.at().tok_type, TokenType::Semicolon) {
+            self.eat();
+        }
+
+        Ok(())
     }
 
-    // For some reason, rust-analyzer thinks tok_type is unused here, which it definitely isn't
-    fn expect(&mut self, tok_type: TokenType, err: &str) -> Token {
-        let prev = self.eat();
+    /// Parses a statement, and if that fails, records the error and skips ahead to the
+    /// start of the next statement.
+    fn parse_stmt_recovering(&mut self, in_block: bool) -> Option<NodeType> {
+        let tokens_left = self.tokens.len();
+
+        // An empty statement.
+        if matches!(self.at().tok_type, TokenType::Semicolon) {
+            self.eat();
+            return None;
+        }
+
+        match self.parse_stmt() {
+            Ok(stmt) => Some(stmt),
+            Err(err) => {
+                self.errors.push(err);
+                self.synchronize(in_block);
+
+                // The statement may have failed on its very first token, which then has to be
+                // skipped for the parser to make any progress.
+                if self.tokens.len() == tokens_left
+                    && self.not_eof()
+                    && !(in_block && matches!(self.at().tok_type, TokenType::CloseBrace))
+                {
+                    self.eat();
+                }
 
-        if prev.tok_type.clone() != tok_type {
-            println!("Parser error:\n {} {:?}", err, prev.tok_type);
-            process::exit(1);
+                None
+            }
         }
+    }
+
+    /// Skips tokens until a statement boundary: past a `;`, or up to a keyword that starts a
+    /// statement or a token on a new line, same as `at_statement_end`. A `}` ends the
+    /// enclosing block, so it's left in place inside blocks.
+    fn synchronize(&mut self, in_block: bool) {
+        while self.not_eof() {
+            if self.at().span.line > self.prev_span.line {
+                return;
+            }
+
+            match self.at().tok_type {
+                TokenType::Semicolon => {
+                    self.eat();
+                    return;
+                }
+                TokenType::CloseBrace => {
+                    if !in_block {
+                        self.eat();
+                    }
 
-        prev
+                    return;
+                }
+                TokenType::Let
+                | TokenType::Const
+                | TokenType::Fn
+                | TokenType::If
+                | TokenType::For
+                | TokenType::While
+                | TokenType::Do
+                | TokenType::Break
+                | TokenType::Continue
+                | TokenType::Return => return,
+                _ => {
+                    self.eat();
+                }
+            }
+        }
     }
 
-    fn parse_stmt(&mut self) -> NodeType {
+    fn parse_stmt(&mut self) -> Result<NodeType, ParseError> {
         match self.at().tok_type {
             TokenType::Let | TokenType::Const => self.parse_var_declaration(),
-            TokenType::Fn => self.parse_function_declaration(),
+            // `fn` without a name is a function expression, like `fn (x) { ... }(1)`.
+            TokenType::Fn
+                if self
+                    .tokens
+                    .get(1)
+                    .is_some_and(|next| next.tok_type == TokenType::Identifier) =>
+            {
+                self.parse_function_declaration()
+            }
             TokenType::If => self.parse_if_statement(),
-            TokenType::For => self.parse_for_statement(),
-            _ => self.parse_expr(),
+            TokenType::For => self.parse_for_statement(None),
+            TokenType::While => self.parse_while_statement(None),
+            TokenType::Do => self.parse_do_while_statement(None),
+            TokenType::Break | TokenType::Continue => self.parse_jump_statement(),
+            TokenType::Return => self.parse_return_statement(),
+            // Braces at the start of a statement are always a block. Object literals can only
+            // appear where an expression is expected, like `let obj = { ... }`.
+            TokenType::OpenBrace => {
+                let start = self.at().span;
+                let body = self.parse_block_statement()?;
+
+                Ok(NodeType::BlockStatement(BlockStatement {
+                    body,
+                    span: self.span_from(start),
+                }))
+            }
+            // Like `if`, a `try` statement ends with its block and needs no `;` after it.
+            TokenType::Identifier if self.at().value == "try" => self.parse_try_catch_statement(),
+            TokenType::Identifier
+                if self
+                    .tokens
+                    .get(1)
+                    .is_some_and(|next| next.tok_type == TokenType::Colon) =>
+            {
+                self.parse_labelled_statement()
+            }
+            _ => {
+                let expr = self.parse_expr()?;
+
+                self.end_statement(
+                    "Semicolon (\";\") or a new line expected at the end of the statement.",
+                )?;
+
+                Ok(expr)
+            }
+        }
+    }
+
+    /// Parses `label: loop`. Only loops can be labelled.
+    fn parse_labelled_statement(&mut self) -> Result<NodeType, ParseError> {
+        let label = self.eat();
+        self.eat();
+
+        match self.at().tok_type {
+            TokenType::For => self.parse_for_statement(Some(label)),
+            TokenType::While => self.parse_while_statement(Some(label)),
+            TokenType::Do => self.parse_do_while_statement(Some(label)),
+            _ => Err(ParseError::InvalidLabel {
+                label: label.value,
+                span: label.span,
+            }),
+        }
+    }
+
+    /// Parses the body of a loop, keeping track of it for `break` and `continue`.
+    fn parse_loop_body(&mut self, label: Option<String>) -> Result<Vec<NodeType>, ParseError> {
+        self.loops.push(label);
+        let body = self.parse_block_statement();
+        self.loops.pop();
+
+        body
+    }
+
+    /// Parses `break` or `continue`, with an optional label on the same line.
+    fn parse_jump_statement(&mut self) -> Result<NodeType, ParseError> {
+        let keyword = self.eat();
+        let label = if self.at().tok_type == TokenType::Identifier
+            && self.at().span.line == keyword.span.line
+        {
+            Some(self.eat())
+        } else {
+            None
+        };
+        let span = self.span_from(keyword.span);
+
+        if self.loops.is_empty() && self.in_try_expr {
+            return Err(ParseError::JumpOutOfTryExpr {
+                keyword: keyword.value,
+                span: keyword.span,
+            });
+        }
+
+        if self.loops.is_empty() {
+            return Err(ParseError::OutsideLoop {
+                keyword: keyword.value,
+                span: keyword.span,
+            });
         }
+
+        if let Some(label) = &label {
+            if !self.loops.contains(&Some(label.value.clone())) {
+                return Err(ParseError::UnknownLabel {
+                    label: label.value.clone(),
+                    span: label.span,
+                });
+            }
+        }
+
+        self.end_statement(
+            "Semicolon (\";\") expected at the end of \"break\"/\"continue\" statement.",
+        )?;
+
+        let label = label.map(|label| label.value);
+
+        Ok(if keyword.tok_type == TokenType::Break {
+            NodeType::BreakStatement(BreakStatement { label, span })
+        } else {
+            NodeType::ContinueStatement(ContinueStatement { label, span })
+        })
     }
 
-    fn parse_block_statement(&mut self) -> Vec<NodeType> {
+    /// Parses `return`, with an optional value on the same line.
+    fn parse_return_statement(&mut self) -> Result<NodeType, ParseError> {
+        let keyword = self.eat();
+
+        if !self.in_function && self.in_try_expr {
+            return Err(ParseError::JumpOutOfTryExpr {
+                keyword: keyword.value,
+                span: keyword.span,
+            });
+        }
+
+        if !self.in_function {
+            return Err(ParseError::OutsideFunction { span: keyword.span });
+        }
+
+        let argument = if self.at_statement_end() {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+        let span = self.span_from(keyword.span);
+
+        self.end_statement("Semicolon (\";\") expected at the end of \"return\" statement.")?;
+
+        Ok(NodeType::ReturnStatement(ReturnStatement {
+            argument,
+            span,
+        }))
+    }
+
+    fn parse_while_statement(&mut self, label: Option<Token>) -> Result<NodeType, ParseError> {
+        let keyword = self.eat();
+        let start = label.as_ref().map_or(keyword.span, |label| label.span);
+        let label = label.map(|label| label.value);
+
+        self.expect(
+            TokenType::OpenParen,
+            "Opening parenthesis (\"(\") expected following \"while\" statement.",
+        )?;
+
+        let test = self.parse_expr()?;
+
+        self.expect(
+            TokenType::CloseParen,
+            "Closing parenthesis (\")\") expected following \"while\" statement.",
+        )?;
+
+        let body = self.parse_loop_body(label.clone())?;
+
+        Ok(NodeType::WhileStatement(WhileStatement {
+            test: Box::new(test),
+            body,
+            label,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn parse_do_while_statement(&mut self, label: Option<Token>) -> Result<NodeType, ParseError> {
+        let keyword = self.eat();
+        let start = label.as_ref().map_or(keyword.span, |label| label.span);
+        let label = label.map(|label| label.value);
+
+        let body = self.parse_loop_body(label.clone())?;
+
+        self.expect(
+            TokenType::While,
+            "\"while\" expected following the body of a \"do\" statement.",
+        )?;
+        self.expect(
+            TokenType::OpenParen,
+            "Opening parenthesis (\"(\") expected following \"while\" in \"do\" statement.",
+        )?;
+
+        let test = self.parse_expr()?;
+
+        self.expect(
+            TokenType::CloseParen,
+            "Closing parenthesis (\")\") expected following \"while\" in \"do\" statement.",
+        )?;
+
+        let span = self.span_from(start);
+
+        if matches!(self.at().tok_type, TokenType::Semicolon) {
+            self.eat();
+        }
+
+        Ok(NodeType::DoWhileStatement(DoWhileStatement {
+            body,
+            test: Box::new(test),
+            label,
+            span,
+        }))
+    }
+
+    fn parse_block_statement(&mut self) -> Result<Vec<NodeType>, ParseError> {
         self.expect(
             TokenType::OpenBrace,
             "Opening brace (\"{\") expected while parsing code block.",
-        );
+        )?;
 
         let mut body: Vec<NodeType> = vec![];
 
         while self.not_eof() && !matches!(self.at().tok_type, TokenType::CloseBrace) {
-            let stmt = self.parse_stmt();
-            body.push(stmt);
+            if let Some(stmt) = self.parse_stmt_recovering(true) {
+                body.push(stmt);
+            }
         }
 
         self.expect(
             TokenType::CloseBrace,
             "Closing brace (\"}\") expected while parsing code block.",
-        );
+        )?;
 
-        body
+        Ok(body)
     }
 
-    fn parse_for_statement(&mut self) -> NodeType {
-        self.eat();
+    fn parse_for_statement(&mut self, label: Option<Token>) -> Result<NodeType, ParseError> {
+        let keyword = self.eat();
+        let start = label.as_ref().map_or(keyword.span, |label| label.span);
+        let label = label.map(|label| label.value);
+
         self.expect(
             TokenType::OpenParen,
             "Opening parenthesis (\"(\") expected following \"for\" statement.",
-        );
+        )?;
 
-        let init = self.parse_var_declaration();
-        let test = self.parse_expr();
+        let init = self.parse_var_declaration()?;
+        let test = self.parse_expr()?;
 
         self.expect(
             TokenType::Semicolon,
             "Semicolon (\";\") expected following \"test expression\" in \"for\" statement.",
-        );
+        )?;
 
-        let update = self.parse_assignment_expr();
+        let update = self.parse_assignment_expr()?;
 
-        self.expect(TokenType::CloseParen, "Closing parenthesis (\"(\") expected following \"additive expression\" in \"for\" statement.");
+        self.expect(TokenType::CloseParen, "Closing parenthesis (\"(\") expected following \"additive expression\" in \"for\" statement.")?;
 
-        let body = self.parse_block_statement();
+        let body = self.parse_loop_body(label.clone())?;
 
-        NodeType::ForStatement(ForStatement {
+        Ok(NodeType::ForStatement(ForStatement {
             body,
+            label,
             init: Box::new(init),
             test: Box::new(test),
             update: Box::new(update),
-        })
+            span: self.span_from(start),
+        }))
     }
 
-    fn parse_if_statement(&mut self) -> NodeType {
-        self.eat();
+    fn parse_if_statement(&mut self) -> Result<NodeType, ParseError> {
+        let start = self.eat().span;
         self.expect(
             TokenType::OpenParen,
             "Opening parenthesis (\"(\") expected following \"if\" statement.",
-        );
+        )?;
 
-        let test = self.parse_expr();
+        let test = self.parse_expr()?;
 
         self.expect(
             TokenType::CloseParen,
             "Closing parenthesis (\"(\") expected following \"if\" statement.",
-        );
+        )?;
 
-        let body = self.parse_block_statement();
+        let body = self.parse_block_statement()?;
 
         let mut alternate: Vec<NodeType> = vec![];
 
@@ -147,292 +661,478 @@ impl Parser {
             self.eat();
 
             if matches!(self.at().tok_type, TokenType::If) {
-                alternate = vec![self.parse_if_statement()];
+                alternate = vec![self.parse_if_statement()?];
             } else {
-                alternate = self.parse_block_statement();
+                alternate = self.parse_block_statement()?;
             }
         }
 
-        NodeType::IfStatement(IfStatement {
+        Ok(NodeType::IfStatement(IfStatement {
             body,
             test: Box::new(test),
             alternate: Some(alternate),
-        })
+            span: self.span_from(start),
+        }))
     }
 
-    fn parse_function_declaration(&mut self) -> NodeType {
-        self.eat();
+    fn parse_function_declaration(&mut self) -> Result<NodeType, ParseError> {
+        let start = self.eat().span;
         let name = self
             .expect(
                 TokenType::Identifier,
                 "Function name expected following \"fn\" statement.",
-            )
+            )?
             .value;
 
-        let args = self.parse_args();
+        let parameters = self.parse_params()?;
+        let body = self.parse_function_body()?;
+
+        Ok(NodeType::FunctionDeclaration(FunctionDeclaration {
+            body,
+            name,
+            parameters,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parses `fn (a, b) { ... }` in an expression.
+    fn parse_function_expr(&mut self) -> Result<NodeType, ParseError> {
+        let start = self.eat().span;
+        let parameters = self.parse_params()?;
+        let body = self.parse_function_body()?;
+
+        Ok(NodeType::FunctionExpr(FunctionExpr {
+            parameters,
+            body,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Whether an arrow function starts here: either `x =>` or a parenthesized list followed
+    /// by `=>`.
+    fn at_arrow_function(&self) -> bool {
+        let is_arrow = |index: usize| {
+            self.tokens
+                .get(index)
+                .is_some_and(|token| token.tok_type == TokenType::Arrow)
+        };
+
+        match self.at().tok_type {
+            TokenType::Identifier => is_arrow(1),
+            TokenType::OpenParen => {
+                let mut depth = 0;
+
+                for (index, token) in self.tokens.iter().enumerate() {
+                    match token.tok_type {
+                        TokenType::OpenParen => depth += 1,
+                        TokenType::CloseParen => depth -= 1,
+                        TokenType::Eof => return false,
+                        _ => {}
+                    }
+
+                    if depth == 0 {
+                        return is_arrow(index + 1);
+                    }
+                }
+
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses `(a, b) => ...` or `a => ...`. The body is either a block or a single expression,
+    /// which is returned.
+    fn parse_arrow_function(&mut self) -> Result<NodeType, ParseError> {
+        let start = self.at().span;
+        let parameters = if matches!(self.at().tok_type, TokenType::Identifier) {
+            vec![self.eat().value]
+        } else {
+            self.parse_params()?
+        };
+
+        self.expect(
+            TokenType::Arrow,
+            "Arrow (\"=>\") expected following arrow function parameters.",
+        )?;
+
+        let body = if matches!(self.at().tok_type, TokenType::OpenBrace) {
+            self.parse_function_body()?
+        } else {
+            let value = self.parse_expr()?;
+
+            vec![NodeType::ReturnStatement(ReturnStatement {
+                span: value.span(),
+                argument: Some(Box::new(value)),
+            })]
+        };
+
+        Ok(NodeType::FunctionExpr(FunctionExpr {
+            parameters,
+            body,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parses a parenthesized parameter list, which can only hold names.
+    fn parse_params(&mut self) -> Result<Vec<String>, ParseError> {
+        let args = self.parse_args()?;
         let mut params: Vec<String> = vec![];
 
         for arg in args.iter() {
             if let NodeType::Identifier(identifier) = arg {
                 params.push(identifier.symbol.clone());
             } else {
-                println!("Arguments for \"fn\" statement must be of type \"String\".");
-                process::exit(1);
+                return Err(ParseError::InvalidParameter { span: arg.span() });
             }
         }
 
+        Ok(params)
+    }
+
+    fn parse_function_body(&mut self) -> Result<Vec<NodeType>, ParseError> {
+        // Loops outside of the function can't be broken out of from inside it.
+        let loops = std::mem::take(&mut self.loops);
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let in_try_expr = std::mem::replace(&mut self.in_try_expr, false);
         let body = self.parse_block_statement();
+        self.loops = loops;
+        self.in_function = in_function;
+        self.in_try_expr = in_try_expr;
 
-        NodeType::FunctionDeclaration(FunctionDeclaration {
-            body,
-            name,
-            parameters: params,
-        })
+        body
     }
 
-    fn parse_var_declaration(&mut self) -> NodeType {
-        let is_constant = matches!(self.eat().tok_type, TokenType::Const);
+    fn parse_var_declaration(&mut self) -> Result<NodeType, ParseError> {
+        let keyword = self.eat();
+        let is_constant = matches!(keyword.tok_type, TokenType::Const);
         let identifier = self
             .expect(
                 TokenType::Identifier,
                 "Variable name expected following \"let\"/\"const\" statement",
-            )
+            )?
             .value;
 
-        if matches!(self.at().tok_type, TokenType::Semicolon) {
-            self.eat();
+        if self.at_statement_end() {
+            let span = self.span_from(keyword.span);
 
             if is_constant {
-                println!("Constant variables must have assigned values.");
-                process::exit(1);
+                return Err(ParseError::MissingConstantValue { identifier, span });
             }
 
-            return NodeType::VarDeclaration(VarDeclaration {
+            if matches!(self.at().tok_type, TokenType::Semicolon) {
+                self.eat();
+            }
+
+            return Ok(NodeType::VarDeclaration(VarDeclaration {
                 constant: false,
                 identifier,
                 value: None,
-            });
+                span,
+            }));
         }
 
-        self.expect(TokenType::Equals, "Equals (\"=\") expected following \"identifier\" declaration in \"let\"/\"const\" statement.");
+        self.expect(TokenType::Equals, "Equals (\"=\") expected following \"identifier\" declaration in \"let\"/\"const\" statement.")?;
 
-        let declaration = NodeType::VarDeclaration(VarDeclaration {
-            constant: is_constant,
-            identifier,
-            value: Some(Box::new(self.parse_expr())),
-        });
+        let value = self.parse_expr()?;
 
         if matches!(self.at().tok_type, TokenType::String) {
             self.eat();
         }
 
-        self.expect(
-            TokenType::Semicolon,
+        let span = self.span_from(keyword.span);
+
+        self.end_statement(
             "Semicolon (\";\") expected at the end of \"let\"/\"const\" statement.",
-        );
+        )?;
 
-        declaration
+        Ok(NodeType::VarDeclaration(VarDeclaration {
+            constant: is_constant,
+            identifier,
+            value: Some(Box::new(value)),
+            span,
+        }))
     }
 
-    fn parse_expr(&mut self) -> NodeType {
+    fn parse_expr(&mut self) -> Result<NodeType, ParseError> {
         self.parse_assignment_expr()
     }
 
-    fn parse_assignment_expr(&mut self) -> NodeType {
-        let left = self.parse_object_expr();
+    fn parse_assignment_expr(&mut self) -> Result<NodeType, ParseError> {
+        if self.at_arrow_function() {
+            return self.parse_arrow_function();
+        }
+
+        let left = self.parse_try_catch_expr()?;
 
         if matches!(self.at().tok_type, TokenType::Equals) {
             self.eat();
-            let value = self.parse_assignment_expr();
+            let value = self.parse_assignment_expr()?;
 
-            return NodeType::AssignmentExpr(AssignmentExpr {
+            return Ok(NodeType::AssignmentExpr(AssignmentExpr {
+                span: left.span().to(value.span()),
                 assign: Box::new(left),
                 value: Box::new(value),
-            });
+            }));
         }
 
-        left
+        Ok(left)
     }
 
-    fn parse_args(&mut self) -> Vec<NodeType> {
+    fn parse_args(&mut self) -> Result<Vec<NodeType>, ParseError> {
         self.expect(
             TokenType::OpenParen,
             "Opening parenthesis (\"(\") expected while parsing arguments.",
-        );
+        )?;
         let args: Vec<NodeType> = if matches!(self.at().tok_type, TokenType::CloseParen) {
             vec![]
         } else {
-            self.parse_args_list()
+            self.parse_args_list()?
         };
 
         self.expect(
             TokenType::CloseParen,
             "Closing parenthesis (\")\") expected while parsing arguments.",
-        );
+        )?;
 
-        args
+        Ok(args)
     }
 
-    fn parse_and_statement(&mut self) -> NodeType {
-        let mut left = self.parse_additive_expr();
-
-        let current_token = self.at();
-
-        if current_token.value == "&&" || current_token.value == "|" {
-            let operator = self.eat().value;
-            let right = self.parse_additive_expr();
-
-            left = NodeType::BinaryExpr(BinaryExpr {
-                left: Box::new(left),
-                right: Box::new(right),
-                operator,
-            })
+    fn parse_try_catch_expr(&mut self) -> Result<NodeType, ParseError> {
+        if self.at().value != "try" {
+            return self.parse_binary_expr(1);
         }
 
-        left
+        // Loops and functions around a `try` used as a value can't be jumped out of from
+        // inside it, the same way as for a function body.
+        let loops = std::mem::take(&mut self.loops);
+        let in_function = std::mem::replace(&mut self.in_function, false);
+        let in_try_expr = std::mem::replace(&mut self.in_try_expr, true);
+        let try_catch = self.parse_try_catch_statement();
+        self.loops = loops;
+        self.in_function = in_function;
+        self.in_try_expr = in_try_expr;
+
+        try_catch
     }
 
-    fn parse_try_catch_expr(&mut self) -> NodeType {
-        if self.at().value != "try" {
-            return self.parse_and_statement();
-        }
+    /// Parses `try { ... } catch { ... }`, as a statement or as a value.
+    fn parse_try_catch_statement(&mut self) -> Result<NodeType, ParseError> {
+        let start = self.eat().span;
 
-        self.eat();
-
-        let body = self.parse_block_statement();
+        let body = self.parse_block_statement()?;
 
         if self.at().value != "catch" {
-            println!("\"try\" statement must be followed by a \"catch\" statement.");
-            process::exit(1);
+            return Err(ParseError::MissingCatch {
+                found: self.at().tok_type.clone(),
+                span: self.at().span,
+            });
         }
 
         self.eat();
 
-        let alternate = self.parse_block_statement();
+        let alternate = self.parse_block_statement()?;
 
-        NodeType::TryCatchStatement(TryCatchStatement { body, alternate })
+        Ok(NodeType::TryCatchStatement(TryCatchStatement {
+            body,
+            alternate,
+            span: self.span_from(start),
+        }))
     }
 
-    fn parse_object_expr(&mut self) -> NodeType {
-        if !matches!(self.at().tok_type, TokenType::OpenBrace) {
-            return self.parse_try_catch_expr();
-        }
-
-        self.eat();
+    /// Parses `{ key: value, shorthand }`. Only reached in expression position, since a `{`
+    /// that starts a statement is a block.
+    fn parse_object_literal(&mut self) -> Result<NodeType, ParseError> {
+        let start = self.eat().span;
 
         let mut properties: Vec<NodeType> = vec![];
 
         while self.not_eof() && !matches!(self.at().tok_type, TokenType::CloseBrace) {
-            let key = self
-                .expect(
-                    TokenType::Identifier,
-                    "Identifier expected following \"Object\" expression.",
-                )
-                .value;
+            let key_token = self.expect(
+                TokenType::Identifier,
+                "Identifier expected following \"Object\" expression.",
+            )?;
+            let key = key_token.value;
 
             if matches!(self.at().tok_type, TokenType::Comma) {
                 self.eat();
-                properties.push(NodeType::Property(Property { key, value: None }));
+                properties.push(NodeType::Property(Property {
+                    key,
+                    value: None,
+                    span: key_token.span,
+                }));
                 continue;
             } else if matches!(self.at().tok_type, TokenType::CloseBrace) {
-                properties.push(NodeType::Property(Property { key, value: None }));
+                properties.push(NodeType::Property(Property {
+                    key,
+                    value: None,
+                    span: key_token.span,
+                }));
                 continue;
             }
 
             self.expect(
                 TokenType::Colon,
                 "Semicolon (\";\") expected following \"identifier\" in \"Object\" expression.",
-            );
-            let value = self.parse_expr();
+            )?;
+            let value = self.parse_expr()?;
 
             properties.push(NodeType::Property(Property {
                 key,
+                span: key_token.span.to(value.span()),
                 value: Some(Box::new(value)),
             }));
 
             if !matches!(self.at().tok_type, TokenType::CloseBrace) {
-                self.expect(TokenType::Comma, "Comma (\";\") or closing brace (\"}\") expected after \"property\" declaration.");
+                self.expect(TokenType::Comma, "Comma (\";\") or closing brace (\"}\") expected after \"property\" declaration.")?;
             }
         }
 
         self.expect(
             TokenType::CloseBrace,
             "Closing brace (\"}\") expected at the end of \"Object\" expression.",
-        );
-        NodeType::ObjectLiteral(ObjectLiteral { properties })
+        )?;
+        Ok(NodeType::ObjectLiteral(ObjectLiteral {
+            properties,
+            span: self.span_from(start),
+        }))
     }
 
-    fn parse_additive_expr(&mut self) -> NodeType {
-        let mut left = self.parse_multiplicative_expr();
+    /// Parses binary operators by precedence climbing: operators binding at least as tightly
+    /// as `min_precedence` are folded into the expression, while looser ones are left for the
+    /// caller.
+    fn parse_binary_expr(&mut self, min_precedence: u8) -> Result<NodeType, ParseError> {
+        let mut left = self.parse_unary_expr()?;
 
-        while vec!["+", "-", "==", "!=", "<", ">"].contains(&mut self.at().value.as_str()) {
+        while let Some(precedence) =
+            binary_precedence(self.at()).filter(|precedence| *precedence >= min_precedence)
+        {
             let operator = self.eat().value;
-            let right = self.parse_multiplicative_expr();
-
-            left = NodeType::BinaryExpr(BinaryExpr {
-                left: Box::new(left),
-                right: Box::new(right),
-                operator,
-            })
+            let right = self.parse_binary_expr(precedence + 1)?;
+            let span = left.span().to(right.span());
+
+            left = if operator == "&&" || operator == "||" {
+                NodeType::LogicalExpr(LogicalExpr {
+                    span,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    operator,
+                })
+            } else {
+                NodeType::BinaryExpr(BinaryExpr {
+                    span,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    operator,
+                })
+            }
         }
 
-        left
+        Ok(left)
     }
 
-    fn parse_multiplicative_expr(&mut self) -> NodeType {
-        let mut left = self.parse_call_member_expr();
+    fn parse_unary_expr(&mut self) -> Result<NodeType, ParseError> {
+        let unary = match self.at().tok_type {
+            TokenType::Exclamation | TokenType::Typeof => true,
+            TokenType::BinaryOperator => ["-", "+"].contains(&self.at().value.as_str()),
+            _ => false,
+        };
 
-        while vec!["/", "*", "%"].contains(&mut self.at().value.as_str()) {
-            let operator = self.eat().value;
-            let right = self.parse_call_member_expr();
+        if !unary {
+            return self.parse_exponent_expr();
+        }
 
-            left = NodeType::BinaryExpr(BinaryExpr {
-                left: Box::new(left),
-                right: Box::new(right),
-                operator,
-            })
+        let operator = self.eat();
+        let exponent_follows = self.tokens.get(1).is_some_and(|next| next.value == "**");
+
+        // A negated literal is folded into one, unless it's the base of `**`, which gets
+        // raised to the power first.
+        if operator.value == "-"
+            && matches!(self.at().tok_type, TokenType::Number | TokenType::BigInt)
+            && !exponent_follows
+        {
+            let token = self.eat();
+            let span = operator.span.to(token.span);
+
+            return parse_number_token(token, true, span);
         }
 
-        left
+        let argument = self.parse_unary_expr()?;
+
+        Ok(NodeType::UnaryExpr(UnaryExpr {
+            span: operator.span.to(argument.span()),
+            operator: operator.value,
+            argument: Box::new(argument),
+        }))
     }
 
-    fn parse_call_member_expr(&mut self) -> NodeType {
-        let member = self.parse_member_expr();
+    /// Parses `**`, which binds tighter than prefix operators like in maths, so `-2 ** 2` is
+    /// `-(2 ** 2)`. It's right-associative, and its exponent can have a prefix operator of
+    /// its own, as in `2 ** -1`.
+    fn parse_exponent_expr(&mut self) -> Result<NodeType, ParseError> {
+        let base = self.parse_call_member_expr()?;
 
-        if matches!(self.at().tok_type, TokenType::OpenParen) {
-            return self.parse_call_expr(member);
+        if !(matches!(self.at().tok_type, TokenType::BinaryOperator) && self.at().value == "**") {
+            return Ok(base);
         }
 
-        member
+        let operator = self.eat().value;
+        let exponent = self.parse_unary_expr()?;
+
+        Ok(NodeType::BinaryExpr(BinaryExpr {
+            span: base.span().to(exponent.span()),
+            left: Box::new(base),
+            right: Box::new(exponent),
+            operator,
+        }))
     }
 
-    fn parse_call_expr(&mut self, caller: NodeType) -> NodeType {
+    /// Calls and member accesses can follow each other in any order, as in `a.b()[c].d()`.
+    fn parse_call_member_expr(&mut self) -> Result<NodeType, ParseError> {
+        let mut expr = self.parse_member_expr()?;
+
+        while matches!(self.at().tok_type, TokenType::OpenParen) {
+            let call_expr = self.parse_call_expr(expr)?;
+            expr = self.parse_member_chain(call_expr)?;
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_call_expr(&mut self, caller: NodeType) -> Result<NodeType, ParseError> {
+        let start = caller.span();
+        let args = self.parse_args()?;
         let mut call_expr = NodeType::CallExpr(CallExpr {
             caller: Box::new(caller),
-            args: self.parse_args(),
+            args,
+            span: self.span_from(start),
         });
 
         if matches!(self.at().tok_type, TokenType::OpenParen) {
-            call_expr = self.parse_call_expr(call_expr);
+            call_expr = self.parse_call_expr(call_expr)?;
         }
 
-        call_expr
+        Ok(call_expr)
     }
 
-    fn parse_args_list(&mut self) -> Vec<NodeType> {
-        let mut args = vec![self.parse_assignment_expr()];
+    fn parse_args_list(&mut self) -> Result<Vec<NodeType>, ParseError> {
+        let mut args = vec![self.parse_assignment_expr()?];
 
         while matches!(self.at().tok_type, TokenType::Comma) {
             self.eat();
-            args.push(self.parse_assignment_expr());
+            args.push(self.parse_assignment_expr()?);
         }
 
-        args
+        Ok(args)
     }
 
-    fn parse_member_expr(&mut self) -> NodeType {
-        let mut object = self.parse_primary_expr();
+    fn parse_member_expr(&mut self) -> Result<NodeType, ParseError> {
+        let object = self.parse_primary_expr()?;
 
+        self.parse_member_chain(object)
+    }
+
+    fn parse_member_chain(&mut self, mut object: NodeType) -> Result<NodeType, ParseError> {
         while matches!(self.at().tok_type, TokenType::Dot)
             || matches!(self.at().tok_type, TokenType::OpenBracket)
         {
@@ -442,64 +1142,138 @@ impl Parser {
 
             if matches!(operator.tok_type, TokenType::Dot) {
                 computed = false;
-                property = self.parse_primary_expr();
+                property = self.parse_primary_expr()?;
 
                 if !matches!(property, NodeType::Identifier(_)) {
-                    println!("Dot operator (\".\") is illegal without right-hand-side (<-) being an Identifier.");
-                    process::exit(1);
+                    return Err(ParseError::InvalidMemberProperty {
+                        span: property.span(),
+                    });
                 }
             } else {
                 computed = true;
-                property = self.parse_expr();
+                property = self.parse_expr()?;
 
-                self.expect(TokenType::CloseBracket, "Closing bracket (\"}\") expected following \"computed value\" in \"Member\" expression.");
+                self.expect(TokenType::CloseBracket, "Closing bracket (\"]\") expected following \"computed value\" in \"Member\" expression.")?;
             }
 
             object = NodeType::MemberExpr(MemberExpr {
+                span: self.span_from(object.span()),
                 object: Box::new(object),
                 property: Box::new(property),
                 computed,
             });
         }
 
-        object
+        Ok(object)
+    }
+
+    /// Parses `[a, b, c]`. A trailing comma is allowed.
+    fn parse_array_literal(&mut self) -> Result<NodeType, ParseError> {
+        let start = self.eat().span;
+        let mut elements: Vec<NodeType> = vec![];
+
+        while self.not_eof() && !matches!(self.at().tok_type, TokenType::CloseBracket) {
+            elements.push(self.parse_expr()?);
+
+            if !matches!(self.at().tok_type, TokenType::CloseBracket) {
+                self.expect(
+                    TokenType::Comma,
+                    "Comma (\",\") or closing bracket (\"]\") expected after array element.",
+                )?;
+            }
+        }
+
+        self.expect(
+            TokenType::CloseBracket,
+            "Closing bracket (\"]\") expected at the end of array literal.",
+        )?;
+
+        Ok(NodeType::ArrayLiteral(ArrayLiteral {
+            elements,
+            span: self.span_from(start),
+        }))
     }
 
-    fn parse_primary_expr(&mut self) -> NodeType {
+    fn parse_template_literal(&mut self) -> Result<NodeType, ParseError> {
+        let head = self.eat();
+        let mut quasis = vec![head.value];
+        let mut expressions: Vec<NodeType> = vec![];
+
+        if matches!(head.tok_type, TokenType::TemplateHead) {
+            loop {
+                expressions.push(self.parse_expr()?);
+
+                if matches!(self.at().tok_type, TokenType::TemplateMiddle) {
+                    quasis.push(self.eat().value);
+                    continue;
+                }
+
+                quasis.push(
+                    self.expect(
+                        TokenType::TemplateTail,
+                        "Closing brace (\"}\") expected following expression in template string.",
+                    )?
+                    .value,
+                );
+                break;
+            }
+        }
+
+        Ok(NodeType::TemplateLiteral(TemplateLiteral {
+            quasis,
+            expressions,
+            span: self.span_from(head.span),
+        }))
+    }
+
+    fn parse_primary_expr(&mut self) -> Result<NodeType, ParseError> {
         let tk = self.at().tok_type.clone();
 
-        match tk {
-            TokenType::Identifier => NodeType::Identifier(Identifier {
-                symbol: self.eat().value,
-            }),
-            TokenType::Number => NodeType::NumericLiteral(NumericLiteral {
-                value: {
-                    let val = self.eat().value;
+        Ok(match tk {
+            TokenType::Identifier => {
+                let token = self.eat();
 
-                    val.parse().expect("Failed to convert String to f32")
-                },
-            }),
-            TokenType::String => NodeType::StringLiteral(StringLiteral {
-                value: self.eat().value,
-            }),
+                NodeType::Identifier(Identifier {
+                    symbol: token.value,
+                    span: token.span,
+                })
+            }
+            TokenType::Number | TokenType::BigInt => {
+                let token = self.eat();
+                let span = token.span;
+
+                parse_number_token(token, false, span)?
+            }
+            TokenType::String => {
+                let token = self.eat();
+
+                NodeType::StringLiteral(StringLiteral {
+                    value: token.value,
+                    span: token.span,
+                })
+            }
+            TokenType::Template | TokenType::TemplateHead => self.parse_template_literal()?,
+            TokenType::OpenBracket => self.parse_array_literal()?,
+            TokenType::OpenBrace => self.parse_object_literal()?,
+            TokenType::Fn => self.parse_function_expr()?,
             TokenType::OpenParen => {
                 self.eat();
-                let value = self.parse_expr();
+                let value = self.parse_expr()?;
 
                 self.expect(
                     TokenType::CloseParen,
                     "Unexpected token (?) found while parsing arguments.",
-                );
+                )?;
 
                 value
             }
             _ => {
-                println!(
-                    "Unexpected token found during parsing! {:?}",
-                    self.at().tok_type
-                );
-                process::exit(1);
+                return Err(ParseError::UnexpectedToken {
+                    found: tk,
+                    value: self.at().value.clone(),
+                    span: self.at().span,
+                });
             }
-        }
+        })
     }
 }
@@ -0,0 +1,40 @@
+use std::fmt::{Display, Formatter};
+
+/// A region of the source code. `start` and `end` are byte offsets, `line` and `column`
+/// point at the first character of the region (both start at 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Creates a span that starts at `self` and ends where `other` ends.
+    pub fn to(self, other: Span) -> Span {
+        if other.end < self.end {
+            return self;
+        }
+
+        Span {
+            end: other.end,
+            ..self
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -1,13 +1,19 @@
 use std::{
     env,
     error::Error,
-    io::{self, Write},
+    io::{self, IsTerminal, Write},
     process,
 };
 
-use frontend::parser::{Parse, Parser};
+use frontend::{
+    errors::ParseError,
+    parser::{Parse, Parser},
+};
 use runtime::environment::create_global_env;
-use utils::transcriber;
+use utils::{
+    diagnostics::{Diagnostic, SourceFile},
+    transcriber,
+};
 
 use crate::runtime::interpreter;
 
@@ -17,34 +23,112 @@ mod utils;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
-
-    match args.get(1) {
-        Some(filename) => run(filename).await?,
-        None => repl().await?,
+    let (flags, args): (Vec<String>, Vec<String>) =
+        env::args().skip(1).partition(|arg| arg.starts_with("--"));
+
+    // Colors are for humans, so they're left out of pipes and CI logs.
+    let color = !flags.iter().any(|flag| flag == "--no-color")
+        && env::var_os("NO_COLOR").is_none()
+        && io::stderr().is_terminal();
+
+    let result = match (args.first().map(String::as_str), args.get(1)) {
+        (Some("check"), Some(filename)) => check(filename, color).await,
+        (Some(filename), _) => run(filename, color).await,
+        (None, _) => repl(color).await,
+    };
+
+    if let Err(err) = result {
+        eprint!(
+            "{}",
+            Diagnostic::from(&*err).render(&SourceFile::new("", String::new(), None), color)
+        );
+        process::exit(1);
     }
 
     Ok(())
 }
 
-async fn run<'a>(filename: &String) -> Result<(), Box<dyn Error>> {
-    let mut parser = Parser::new();
-    let env = create_global_env()?;
-
-    let mut input = tokio::fs::read_to_string(filename).await?;
+/// Reads a file and transcribes it if it's BSX. Returns the code to run, and the file as the
+/// user wrote it for error reporting.
+async fn read_source(filename: &str) -> Result<(String, SourceFile), Box<dyn Error>> {
+    let input = tokio::fs::read_to_string(filename).await?;
 
     if filename.ends_with(".bsx") {
-        input = transcriber::transcribe(input);
+        let (code, map) = transcriber::transcribe(&input);
+
+        return Ok((code, SourceFile::new(filename, input, Some(map))));
     }
 
-    let program = parser.create_ast(input);
-    let result = interpreter::evaluate(&program, &env)?;
+    Ok((input.clone(), SourceFile::new(filename, input, None)))
+}
+
+fn report_parse_errors(errors: &[ParseError], source: &SourceFile, color: bool) {
+    for err in errors {
+        let mut diagnostic = Diagnostic::from(err);
+
+        let names_tokens = matches!(
+            err,
+            ParseError::ExpectedToken { .. } | ParseError::UnexpectedToken { .. }
+        );
+
+        if source.map.is_some() && names_tokens {
+            diagnostic = diagnostic.with_note(
+                "BSX is transcribed before parsing, so tokens are named after plain bussin"
+                    .to_string(),
+            );
+        }
+
+        eprint!("{}", diagnostic.render(source, color));
+    }
+}
+
+fn report_error(err: &(dyn Error + 'static), source: &SourceFile, color: bool) {
+    eprint!("{}", Diagnostic::from(err).render(source, color));
+}
+
+async fn run(filename: &str, color: bool) -> Result<(), Box<dyn Error>> {
+    let mut parser = Parser::new();
+    let env = create_global_env()?;
+
+    let (input, source) = read_source(filename).await?;
+
+    let program = match parser.create_ast(input) {
+        Ok(program) => program,
+        Err(errors) => {
+            report_parse_errors(&errors, &source, color);
+            process::exit(1);
+        }
+    };
+    let result = match interpreter::evaluate(&program, &env) {
+        Ok(result) => result,
+        Err(err) => {
+            report_error(&*err, &source, color);
+            process::exit(1);
+        }
+    };
 
     println!("{:?}", result);
     Ok(())
 }
 
-async fn repl() -> Result<(), Box<dyn Error>> {
+/// Parses a file without running it, reporting every syntax error in it.
+async fn check(filename: &str, color: bool) -> Result<(), Box<dyn Error>> {
+    let mut parser = Parser::new();
+    let (input, source) = read_source(filename).await?;
+
+    let (_, errors) = parser.parse_recovering(input);
+
+    if !errors.is_empty() {
+        report_parse_errors(&errors, &source, color);
+        eprintln!("{}: {} error(s) found.", filename, errors.len());
+        process::exit(1);
+    }
+
+    println!("{}: no errors found.", filename);
+    Ok(())
+}
+
+async fn repl(color: bool) -> Result<(), Box<dyn Error>> {
     let mut parser = Parser::new();
 
     println!("Repl v1.0 (Rusted Bussin)");
@@ -56,17 +140,34 @@ async fn repl() -> Result<(), Box<dyn Error>> {
         io::stdout().flush().expect("Failed to flush io::stdout");
 
         print!("> ");
-        io::stdin()
+
+        let previous_len = input.len();
+        let read = io::stdin()
             .read_line(&mut input)
             .expect("Failed to read line");
 
-        if input == "" || input == "exit" {
+        if read == 0 || input[previous_len..].trim() == "exit" {
             process::exit(1);
         }
 
-        let program = parser.create_ast(input.clone());
-        let result = interpreter::evaluate(&program, &env)?;
-
-        println!("{:?}", result);
+        let source = SourceFile::new("<repl>", input.clone(), None);
+
+        // A line that fails to parse or run is dropped, so it doesn't poison the next ones.
+        let program = match parser.create_ast(input.clone()) {
+            Ok(program) => program,
+            Err(errors) => {
+                report_parse_errors(&errors, &source, color);
+                input.truncate(previous_len);
+                continue;
+            }
+        };
+
+        match interpreter::evaluate(&program, &env) {
+            Ok(result) => println!("{:?}", result),
+            Err(err) => {
+                report_error(&*err, &source, color);
+                input.truncate(previous_len);
+            }
+        }
     }
 }
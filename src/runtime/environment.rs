@@ -3,19 +3,19 @@ use std::{
     cell::RefCell,
     collections::HashMap,
     error::Error,
-    f32::consts,
+    f64::consts,
     fmt::{Debug, Formatter},
-    rc::Rc,
+    rc::{Rc, Weak},
 };
 
-use crate::frontend::ast::{Identifier, MemberExpr, NodeType};
+use indexmap::IndexMap;
 
 use super::{
     eval::native_fns::{
-        exec, format, input, math_abs, math_ceil, math_random, math_round, math_sqrt, print_values,
-        strcon, time_function,
+        bigint, deep_equal, exec, format, input, math_abs, math_ceil, math_random, math_round,
+        math_sqrt, number, print_values, strcon, time_function,
     },
-    values::{mk_bool, mk_native_fn, mk_null, mk_number, mk_object, ObjectVal, ValueType},
+    values::{mk_bool, mk_native_fn, mk_null, mk_number, mk_object, ValueType},
 };
 
 pub fn create_global_env() -> Result<Rc<RefCell<dyn EnvironmentScope>>, Box<dyn Error>> {
@@ -36,7 +36,7 @@ pub fn create_global_env() -> Result<Rc<RefCell<dyn EnvironmentScope>>, Box<dyn
     env.borrow()
         .declare_var("input", mk_native_fn("input", input), true)?;
 
-    let mut math: HashMap<String, ValueType> = HashMap::new();
+    let mut math: IndexMap<String, ValueType> = IndexMap::new();
 
     math.insert("pi".to_string(), mk_number(Some(consts::PI)));
     math.insert("sqrt".to_string(), mk_native_fn("math.sqrt", math_sqrt));
@@ -55,6 +55,12 @@ pub fn create_global_env() -> Result<Rc<RefCell<dyn EnvironmentScope>>, Box<dyn
         .declare_var("format", mk_native_fn("format", format), true)?;
     env.borrow()
         .declare_var("time", mk_native_fn("time", time_function), true)?;
+    env.borrow()
+        .declare_var("bigint", mk_native_fn("bigint", bigint), true)?;
+    env.borrow()
+        .declare_var("number", mk_native_fn("number", number), true)?;
+    env.borrow()
+        .declare_var("deepEqual", mk_native_fn("deepEqual", deep_equal), true)?;
 
     Ok(env)
 }
@@ -62,6 +68,9 @@ pub fn create_global_env() -> Result<Rc<RefCell<dyn EnvironmentScope>>, Box<dyn
 #[derive(Debug)]
 pub struct Environment {
     parent: Option<Rc<RefCell<dyn EnvironmentScope>>>,
+    /// The `Rc` this scope lives in, so `resolve` can hand out the scope itself instead of a
+    /// copy of it.
+    this: Weak<RefCell<Environment>>,
     variables: RefCell<HashMap<String, ValueType>>,
     constants: RefCell<Vec<String>>,
 }
@@ -74,23 +83,20 @@ pub trait EnvironmentScope {
         constant: bool,
     ) -> Result<ValueType, Box<dyn Error>>;
     fn assign_var(&self, varname: String, value: ValueType) -> Result<ValueType, Box<dyn Error>>;
-    fn lookup_or_mut_object(
-        &self,
-        expr: MemberExpr,
-        value: Option<ValueType>,
-        property: Option<Identifier>,
-    ) -> Result<ValueType, Box<dyn Error>>;
     fn lookup_var(&self, varname: String) -> Result<ValueType, Box<dyn Error>>;
     fn resolve(&self, varname: String) -> Result<Rc<RefCell<Environment>>, Box<dyn Error>>;
 }
 
 impl Environment {
     pub(crate) fn new(parent_env: Option<Rc<RefCell<dyn EnvironmentScope>>>) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(Self {
-            parent: parent_env,
-            variables: RefCell::new(HashMap::new()),
-            constants: RefCell::new(vec![]),
-        }))
+        Rc::new_cyclic(|this| {
+            RefCell::new(Self {
+                parent: parent_env,
+                this: this.clone(),
+                variables: RefCell::new(HashMap::new()),
+                constants: RefCell::new(vec![]),
+            })
+        })
     }
 }
 
@@ -100,16 +106,6 @@ impl Debug for dyn EnvironmentScope {
     }
 }
 
-impl Clone for Environment {
-    fn clone(&self) -> Self {
-        Self {
-            parent: self.parent.clone(),
-            variables: self.variables.clone(),
-            constants: self.constants.clone(),
-        }
-    }
-}
-
 impl EnvironmentScope for Environment {
     fn declare_var(
         &self,
@@ -129,9 +125,7 @@ impl EnvironmentScope for Environment {
             .insert(varname.to_string(), value.clone());
 
         if constant {
-            self.constants
-                .borrow_mut()
-                .push(varname.to_string());
+            self.constants.borrow_mut().push(varname.to_string());
         }
 
         Ok(value)
@@ -139,107 +133,37 @@ impl EnvironmentScope for Environment {
 
     fn assign_var(&self, varname: String, value: ValueType) -> Result<ValueType, Box<dyn Error>> {
         let env = self.resolve(varname.clone())?;
+        let env = env.borrow();
 
-        if env.borrow().constants.borrow().contains(&varname) {
+        if env.constants.borrow().contains(&varname) {
             Err(format!(
                 "Cannot reassign to variable '{}' as it's constant",
                 varname
             ))?
         }
 
-        self.variables
-            .borrow_mut()
-            .insert(varname.clone(), value.clone());
+        env.variables.borrow_mut().insert(varname, value.clone());
 
         Ok(value)
     }
 
-    fn lookup_or_mut_object(
-        &self,
-        expr: MemberExpr,
-        value: Option<ValueType>,
-        property: Option<Identifier>,
-    ) -> Result<ValueType, Box<dyn Error>> {
-        if let NodeType::MemberExpr(inner_expr) = *expr.object.clone() {
-            return self.lookup_or_mut_object(
-                inner_expr,
-                value,
-                if let NodeType::Identifier(identifier) = *expr.property.clone() {
-                    Some(identifier)
-                } else {
-                    Err(format!("`{:?}` is not an Identifier", expr.object))?
-                },
-            );
-        }
-
-        let varname = if let NodeType::Identifier(identifier) = *expr.object.clone() {
-            identifier.symbol.clone()
-        } else {
-            Err(format!("'{:?}' is not an Identifier", expr.object))?
-        };
-
-        let env = Rc::clone(&self.resolve(varname.clone())?);
-        let mut binding = env.borrow_mut();
-        let mut past_val: &mut ObjectVal = match binding
-            .variables
-            .get_mut()
-            .get_mut(&varname)
-            .expect(format!("Variable '{}' doesn't exist", varname).as_str())
-        {
-            ValueType::ObjectVal(object_val) => object_val,
-            _ => Err(format!("'{}' is not an ObjectVal", varname))?,
-        };
-
-        let prop = if let Some(identifier) = property {
-            identifier.symbol
-        } else {
-            match *expr.property.clone() {
-                NodeType::Identifier(identifier) => identifier.symbol,
-                _ => Err(format!("'{:?}' is not an Identifier", expr.object))?,
-            }
-        };
-
-        let current_prop = match *expr.property.clone() {
-            NodeType::Identifier(identifier) => Some(identifier.symbol),
-            _ => None,
-        };
-
-        if let Some(defined_value) = value {
-            past_val.properties.insert(prop, defined_value);
-        }
-
-        if let Some(defined_current_prop) = current_prop.clone() {
-            past_val = match past_val
-                .properties
-                .get_mut(&defined_current_prop)
-                .expect("&current_prop doesn't exist.")
-            {
-                ValueType::ObjectVal(object_val) => object_val,
-                _ => Err(format!(
-                    "'{}' is not an ObjectVal",
-                    current_prop.unwrap_or("undefined".to_string())
-                ))?,
-            };
-        }
-
-        Ok(ValueType::ObjectVal(past_val.clone()))
-    }
-
     fn lookup_var(&self, varname: String) -> Result<ValueType, Box<dyn Error>> {
-        let env = Rc::clone(&self.resolve(varname.clone())?);
-        let mut binding = env.borrow_mut();
+        let env = self.resolve(varname.clone())?;
+        let binding = env.borrow();
+        let variables = binding.variables.borrow();
 
-        Ok(binding
-            .variables
-            .get_mut()
+        Ok(variables
             .get(&varname)
-            .expect(format!("'{}' does not exist", varname).as_str())
+            .unwrap_or_else(|| panic!("'{}' does not exist", varname))
             .clone())
     }
 
     fn resolve(&self, varname: String) -> Result<Rc<RefCell<Environment>>, Box<dyn Error>> {
         if self.variables.borrow().contains_key(&varname) {
-            return Ok(Rc::new(RefCell::new(self.clone())));
+            return Ok(self
+                .this
+                .upgrade()
+                .expect("Scopes only live inside the Rc they were created in"));
         }
 
         match &self.parent {
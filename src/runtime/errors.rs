@@ -0,0 +1,42 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+use crate::frontend::span::Span;
+
+/// An error raised while evaluating a program, tagged with the span of the node that caused it.
+#[derive(Debug)]
+pub struct RuntimeError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at {})", self.message, self.span)
+    }
+}
+
+impl Error for RuntimeError {}
+
+/// Attaches `span` to an error, unless a more specific span was already attached by a
+/// node deeper in the tree.
+pub fn with_span(err: Box<dyn Error>, span: Span) -> Box<dyn Error> {
+    if err.is::<RuntimeError>() {
+        return err;
+    }
+
+    Box::new(RuntimeError {
+        message: err.to_string(),
+        span,
+    })
+}
+
+/// The message of an error without any location information.
+pub fn error_message(err: &(dyn Error + 'static)) -> String {
+    match err.downcast_ref::<RuntimeError>() {
+        Some(runtime_error) => runtime_error.message.clone(),
+        None => err.to_string(),
+    }
+}
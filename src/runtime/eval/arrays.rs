@@ -0,0 +1,483 @@
+use std::{cell::RefCell, cmp::Ordering, error::Error, rc::Rc};
+
+use crate::runtime::{
+    environment::EnvironmentScope,
+    values::{mk_array, mk_bool, mk_integer, mk_null, mk_string, ArrayVal, ValueType},
+};
+
+use super::{
+    equality::{compare, strict_equals},
+    expressions::call_function,
+    native_fns::match_type,
+};
+
+/// A method on arrays. It gets the array it was called on, and methods that change the
+/// array do so in place, so every reference to it sees the change.
+pub type ArrayMethod = fn(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>>;
+
+const ARRAY_METHODS: &[(&str, ArrayMethod); 19] = &[
+    ("push", push),
+    ("pop", pop),
+    ("shift", shift),
+    ("unshift", unshift),
+    ("slice", slice),
+    ("splice", splice),
+    ("concat", concat),
+    ("indexOf", index_of),
+    ("includes", includes),
+    ("join", join),
+    ("reverse", reverse),
+    ("sort", sort),
+    ("map", map),
+    ("filter", filter),
+    ("reduce", reduce),
+    ("find", find),
+    ("some", some),
+    ("every", every),
+    ("forEach", for_each),
+];
+
+pub fn array_method(name: &str) -> Option<ArrayMethod> {
+    ARRAY_METHODS
+        .iter()
+        .find(|(method, _)| *method == name)
+        .map(|(_, call)| *call)
+}
+
+/// A copy of the elements, for methods that call back into bussin while going through them.
+/// The callback is free to change the array in the meantime.
+fn snapshot(array: &Rc<RefCell<ArrayVal>>) -> Vec<ValueType> {
+    array.borrow().elements.clone()
+}
+
+pub(super) fn integer_arg(
+    arg: Option<&ValueType>,
+    method: &str,
+) -> Result<Option<i64>, Box<dyn Error>> {
+    match arg {
+        None | Some(ValueType::NullVal) => Ok(None),
+        Some(ValueType::IntegerVal(integer_val)) => Ok(Some(integer_val.value)),
+        Some(arg) => Err(format!(
+            "Index passed to {}() must be an integer, found {}",
+            method,
+            match_type(arg.clone())
+        ))?,
+    }
+}
+
+/// Turns an index that may count from the end, like `-1`, into one inside `0..=len`.
+pub(super) fn relative_index(index: i64, len: usize) -> usize {
+    if index < 0 {
+        len.saturating_sub(index.unsigned_abs() as usize)
+    } else {
+        (index as usize).min(len)
+    }
+}
+
+fn callback_arg(args: &[ValueType], method: &str) -> Result<ValueType, Box<dyn Error>> {
+    match args.first() {
+        Some(function @ (ValueType::FunctionVal(_) | ValueType::NativeFnVal(_))) => {
+            Ok(function.clone())
+        }
+        _ => Err(format!("{}() requires a function", method))?,
+    }
+}
+
+/// Calls a callback that decides something about an element, like the one given to
+/// `filter()`, which has to answer with a boolean.
+fn test_element(
+    callback: &ValueType,
+    element: &ValueType,
+    index: usize,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+    method: &str,
+) -> Result<bool, Box<dyn Error>> {
+    match call_function(
+        callback.clone(),
+        vec![element.clone(), mk_integer(Some(index as i64))],
+        env,
+    )? {
+        ValueType::BooleanVal(boolean_val) => Ok(boolean_val.value),
+        result => Err(format!(
+            "Callback passed to {}() must return a boolean, found {}",
+            method,
+            match_type(result)
+        ))?,
+    }
+}
+
+fn push(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let elements = &mut array.borrow_mut().elements;
+
+    elements.extend(args);
+
+    Ok(mk_integer(Some(elements.len() as i64)))
+}
+
+fn pop(
+    array: &Rc<RefCell<ArrayVal>>,
+    _: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    Ok(array.borrow_mut().elements.pop().unwrap_or(mk_null()))
+}
+
+fn shift(
+    array: &Rc<RefCell<ArrayVal>>,
+    _: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let elements = &mut array.borrow_mut().elements;
+
+    if elements.is_empty() {
+        return Ok(mk_null());
+    }
+
+    Ok(elements.remove(0))
+}
+
+fn unshift(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let elements = &mut array.borrow_mut().elements;
+
+    elements.splice(0..0, args);
+
+    Ok(mk_integer(Some(elements.len() as i64)))
+}
+
+fn slice(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let elements = &array.borrow().elements;
+    let len = elements.len();
+    let start = relative_index(integer_arg(args.first(), "slice")?.unwrap_or(0), len);
+    let end = relative_index(
+        integer_arg(args.get(1), "slice")?.unwrap_or(len as i64),
+        len,
+    );
+
+    Ok(mk_array(elements[start..end.max(start)].to_vec()))
+}
+
+/// Removes `count` elements from `start` and puts the rest of the arguments in their place.
+/// Gives back what was removed.
+fn splice(
+    array: &Rc<RefCell<ArrayVal>>,
+    mut args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let elements = &mut array.borrow_mut().elements;
+    let len = elements.len();
+    let start = relative_index(integer_arg(args.first(), "splice")?.unwrap_or(0), len);
+    let count = match integer_arg(args.get(1), "splice")? {
+        Some(count) => count.clamp(0, (len - start) as i64) as usize,
+        None => len - start,
+    };
+    let items = args.drain(..).skip(2);
+
+    Ok(mk_array(
+        elements.splice(start..start + count, items).collect(),
+    ))
+}
+
+/// Arrays passed to `concat()` are flattened by one level, anything else is added as-is.
+fn concat(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let mut elements = snapshot(array);
+
+    for arg in args {
+        match arg {
+            ValueType::ArrayVal(array_val) => elements.extend(snapshot(&array_val)),
+            arg => elements.push(arg),
+        }
+    }
+
+    Ok(mk_array(elements))
+}
+
+fn index_of(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let value = args.first().cloned().unwrap_or(mk_null());
+    let index = array
+        .borrow()
+        .elements
+        .iter()
+        .position(|element| strict_equals(element, &value));
+
+    Ok(mk_integer(Some(index.map_or(-1, |index| index as i64))))
+}
+
+fn includes(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let value = args.first().cloned().unwrap_or(mk_null());
+
+    Ok(mk_bool(Some(
+        array
+            .borrow()
+            .elements
+            .iter()
+            .any(|element| strict_equals(element, &value)),
+    )))
+}
+
+fn join(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let separator = match args.first() {
+        None => ",".to_string(),
+        Some(ValueType::StringVal(string_val)) => string_val.value.clone(),
+        Some(_) => Err("Separator passed to join() must be of type StringVal")?,
+    };
+    let elements: Vec<String> = snapshot(array).into_iter().map(match_type).collect();
+
+    Ok(mk_string(elements.join(&separator)))
+}
+
+fn reverse(
+    array: &Rc<RefCell<ArrayVal>>,
+    _: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    array.borrow_mut().elements.reverse();
+
+    Ok(ValueType::ArrayVal(array.clone()))
+}
+
+/// Without a comparator, numbers sort by value and strings alphabetically.
+fn default_compare(lhs: &ValueType, rhs: &ValueType) -> Result<Ordering, Box<dyn Error>> {
+    match compare(lhs, rhs) {
+        Some(ordering) => Ok(ordering),
+        None => Err(format!(
+            "Cannot compare {} and {} in sort(), pass a comparator instead",
+            match_type(lhs.clone()),
+            match_type(rhs.clone())
+        ))?,
+    }
+}
+
+/// A stable merge sort. The standard library's sorts can panic when the comparator isn't a
+/// total order, which a comparator written in bussin can't promise, and they can't stop on
+/// an error either.
+fn merge_sort<F>(
+    mut elements: Vec<ValueType>,
+    compare: &mut F,
+) -> Result<Vec<ValueType>, Box<dyn Error>>
+where
+    F: FnMut(&ValueType, &ValueType) -> Result<Ordering, Box<dyn Error>>,
+{
+    if elements.len() <= 1 {
+        return Ok(elements);
+    }
+
+    let right = elements.split_off(elements.len() / 2);
+    let mut left = merge_sort(elements, compare)?.into_iter().peekable();
+    let mut right = merge_sort(right, compare)?.into_iter().peekable();
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+
+    while let (Some(lhs), Some(rhs)) = (left.peek(), right.peek()) {
+        // Equal elements keep their order by taking from the left first.
+        let next = if compare(rhs, lhs)? == Ordering::Less {
+            right.next()
+        } else {
+            left.next()
+        };
+
+        merged.extend(next);
+    }
+
+    merged.extend(left);
+    merged.extend(right);
+
+    Ok(merged)
+}
+
+/// Sorts in place. A comparator gets two elements and returns a negative number if the
+/// first goes first, a positive one if it goes last, and zero if it doesn't matter.
+fn sort(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let comparator = match args.first() {
+        None => None,
+        Some(_) => Some(callback_arg(&args, "sort")?),
+    };
+
+    let mut compare = |lhs: &ValueType, rhs: &ValueType| -> Result<Ordering, Box<dyn Error>> {
+        let Some(comparator) = &comparator else {
+            return default_compare(lhs, rhs);
+        };
+
+        match call_function(comparator.clone(), vec![lhs.clone(), rhs.clone()], env)? {
+            ValueType::IntegerVal(integer_val) => Ok(integer_val.value.cmp(&0)),
+            ValueType::NumberVal(number_val) => Ok(number_val
+                .value
+                .partial_cmp(&0.0)
+                .unwrap_or(Ordering::Equal)),
+            result => Err(format!(
+                "Comparator passed to sort() must return a number, found {}",
+                match_type(result)
+            ))?,
+        }
+    };
+
+    let sorted = merge_sort(snapshot(array), &mut compare)?;
+    array.borrow_mut().elements = sorted;
+
+    Ok(ValueType::ArrayVal(array.clone()))
+}
+
+fn map(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let callback = callback_arg(&args, "map")?;
+    let mut elements = vec![];
+
+    for (index, element) in snapshot(array).iter().enumerate() {
+        elements.push(call_function(
+            callback.clone(),
+            vec![element.clone(), mk_integer(Some(index as i64))],
+            env,
+        )?);
+    }
+
+    Ok(mk_array(elements))
+}
+
+fn filter(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let callback = callback_arg(&args, "filter")?;
+    let mut elements = vec![];
+
+    for (index, element) in snapshot(array).iter().enumerate() {
+        if test_element(&callback, element, index, env, "filter")? {
+            elements.push(element.clone());
+        }
+    }
+
+    Ok(mk_array(elements))
+}
+
+/// Folds the array into a single value, starting from the second argument, or from the
+/// first element if there isn't one.
+fn reduce(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let callback = callback_arg(&args, "reduce")?;
+    let mut elements = snapshot(array).into_iter().enumerate();
+
+    let mut accumulator = match args.get(1) {
+        Some(initial) => initial.clone(),
+        None => match elements.next() {
+            Some((_, first)) => first,
+            None => Err("reduce() of an empty array needs an initial value")?,
+        },
+    };
+
+    for (index, element) in elements {
+        accumulator = call_function(
+            callback.clone(),
+            vec![accumulator, element, mk_integer(Some(index as i64))],
+            env,
+        )?;
+    }
+
+    Ok(accumulator)
+}
+
+fn find(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let callback = callback_arg(&args, "find")?;
+
+    for (index, element) in snapshot(array).iter().enumerate() {
+        if test_element(&callback, element, index, env, "find")? {
+            return Ok(element.clone());
+        }
+    }
+
+    Ok(mk_null())
+}
+
+fn some(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let callback = callback_arg(&args, "some")?;
+
+    for (index, element) in snapshot(array).iter().enumerate() {
+        if test_element(&callback, element, index, env, "some")? {
+            return Ok(mk_bool(Some(true)));
+        }
+    }
+
+    Ok(mk_bool(Some(false)))
+}
+
+fn every(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let callback = callback_arg(&args, "every")?;
+
+    for (index, element) in snapshot(array).iter().enumerate() {
+        if !test_element(&callback, element, index, env, "every")? {
+            return Ok(mk_bool(Some(false)));
+        }
+    }
+
+    Ok(mk_bool(Some(true)))
+}
+
+fn for_each(
+    array: &Rc<RefCell<ArrayVal>>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let callback = callback_arg(&args, "forEach")?;
+
+    for (index, element) in snapshot(array).iter().enumerate() {
+        call_function(
+            callback.clone(),
+            vec![element.clone(), mk_integer(Some(index as i64))],
+            env,
+        )?;
+    }
+
+    Ok(mk_null())
+}
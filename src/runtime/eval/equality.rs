@@ -0,0 +1,169 @@
+use std::{cmp::Ordering, rc::Rc};
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use crate::runtime::values::{mk_integer, mk_number, ValueType};
+
+/// Any number as a float, BigInts included. Only used to compare them, where losing a
+/// little precision on huge values doesn't matter.
+fn to_float(value: &ValueType) -> Option<f64> {
+    match value {
+        ValueType::NumberVal(number_val) => Some(number_val.value),
+        ValueType::IntegerVal(integer_val) => Some(integer_val.value as f64),
+        ValueType::BigIntVal(bigint_val) => bigint_val.value.to_f64(),
+        _ => None,
+    }
+}
+
+/// Orders two numbers of any kind. Integers and BigInts are compared exactly, and anything
+/// involving NaN is unordered.
+fn compare_numbers(lhs: &ValueType, rhs: &ValueType) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (ValueType::IntegerVal(lhs), ValueType::IntegerVal(rhs)) => Some(lhs.value.cmp(&rhs.value)),
+        (ValueType::BigIntVal(lhs), ValueType::BigIntVal(rhs)) => Some(lhs.value.cmp(&rhs.value)),
+        (ValueType::BigIntVal(lhs), ValueType::IntegerVal(rhs)) => {
+            Some(lhs.value.cmp(&BigInt::from(rhs.value)))
+        }
+        (ValueType::IntegerVal(lhs), ValueType::BigIntVal(rhs)) => {
+            Some(BigInt::from(lhs.value).cmp(&rhs.value))
+        }
+        _ => to_float(lhs)?.partial_cmp(&to_float(rhs)?),
+    }
+}
+
+/// How `<`, `>`, `<=` and `>=` order two values. Numbers compare by value and strings
+/// lexicographically. Anything else, including a string against a number, is unordered.
+pub fn compare(lhs: &ValueType, rhs: &ValueType) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (ValueType::StringVal(lhs), ValueType::StringVal(rhs)) => Some(lhs.value.cmp(&rhs.value)),
+        _ => compare_numbers(lhs, rhs),
+    }
+}
+
+/// `===`. Both sides have to be the same type, and integers and floats are both numbers, so
+/// `1 === 1.0`. Objects, arrays and functions are only equal to themselves.
+pub fn strict_equals(lhs: &ValueType, rhs: &ValueType) -> bool {
+    match (lhs, rhs) {
+        (ValueType::NullVal, ValueType::NullVal) => true,
+        (ValueType::BooleanVal(lhs), ValueType::BooleanVal(rhs)) => lhs.value == rhs.value,
+        (ValueType::StringVal(lhs), ValueType::StringVal(rhs)) => lhs.value == rhs.value,
+        (ValueType::BigIntVal(lhs), ValueType::BigIntVal(rhs)) => lhs.value == rhs.value,
+        (
+            ValueType::IntegerVal(_) | ValueType::NumberVal(_),
+            ValueType::IntegerVal(_) | ValueType::NumberVal(_),
+        ) => compare_numbers(lhs, rhs) == Some(Ordering::Equal),
+        (ValueType::ObjectVal(lhs), ValueType::ObjectVal(rhs)) => Rc::ptr_eq(lhs, rhs),
+        (ValueType::ArrayVal(lhs), ValueType::ArrayVal(rhs)) => Rc::ptr_eq(lhs, rhs),
+        (ValueType::FunctionVal(lhs), ValueType::FunctionVal(rhs)) => {
+            lhs.body == rhs.body && Rc::ptr_eq(&lhs.declaration_env, &rhs.declaration_env)
+        }
+        (ValueType::NativeFnVal(lhs), ValueType::NativeFnVal(rhs)) => {
+            std::ptr::fn_addr_eq(lhs.call, rhs.call)
+        }
+        _ => false,
+    }
+}
+
+/// A string as the number it spells out, if it does. Blank strings are zero.
+fn string_to_number(value: &str) -> Option<ValueType> {
+    let value = value.trim();
+
+    if value.is_empty() {
+        return Some(mk_integer(Some(0)));
+    }
+
+    if let Ok(integer) = value.parse::<i64>() {
+        return Some(mk_integer(Some(integer)));
+    }
+
+    value
+        .parse::<f64>()
+        .ok()
+        .map(|number| mk_number(Some(number)))
+}
+
+/// `==`. Like `===`, except that numbers of any kind compare by value, and booleans and
+/// strings are turned into numbers when compared against one, so `"1" == 1` and `true == 1`.
+/// `null` is still only equal to itself.
+pub fn loose_equals(lhs: &ValueType, rhs: &ValueType) -> bool {
+    match (lhs, rhs) {
+        (ValueType::BooleanVal(_), ValueType::BooleanVal(_))
+        | (ValueType::StringVal(_), ValueType::StringVal(_)) => strict_equals(lhs, rhs),
+        (ValueType::BooleanVal(boolean_val), other)
+        | (other, ValueType::BooleanVal(boolean_val)) => {
+            loose_equals(&mk_integer(Some(boolean_val.value as i64)), other)
+        }
+        (ValueType::StringVal(string_val), other) | (other, ValueType::StringVal(string_val))
+            if to_float(other).is_some() =>
+        {
+            string_to_number(&string_val.value)
+                .is_some_and(|number| compare_numbers(&number, other) == Some(Ordering::Equal))
+        }
+        _ if to_float(lhs).is_some() && to_float(rhs).is_some() => {
+            compare_numbers(lhs, rhs) == Some(Ordering::Equal)
+        }
+        _ => strict_equals(lhs, rhs),
+    }
+}
+
+/// Compares arrays and objects by their contents, all the way down. Everything else is
+/// compared with `===`.
+pub fn deep_equals(lhs: &ValueType, rhs: &ValueType) -> bool {
+    deep_equals_inner(lhs, rhs, &mut vec![])
+}
+
+/// `comparing` holds the pairs of arrays and objects being compared further up, so
+/// structures that contain themselves don't send this around in circles. A pair that's
+/// already being compared is taken to be equal, and any difference is found elsewhere.
+fn deep_equals_inner(
+    lhs: &ValueType,
+    rhs: &ValueType,
+    comparing: &mut Vec<(*const (), *const ())>,
+) -> bool {
+    let pair = match (lhs, rhs) {
+        (ValueType::ArrayVal(lhs), ValueType::ArrayVal(rhs)) => {
+            (Rc::as_ptr(lhs) as *const (), Rc::as_ptr(rhs) as *const ())
+        }
+        (ValueType::ObjectVal(lhs), ValueType::ObjectVal(rhs)) => {
+            (Rc::as_ptr(lhs) as *const (), Rc::as_ptr(rhs) as *const ())
+        }
+        _ => return strict_equals(lhs, rhs),
+    };
+
+    if pair.0 == pair.1 || comparing.contains(&pair) {
+        return true;
+    }
+
+    comparing.push(pair);
+
+    let equal = match (lhs, rhs) {
+        (ValueType::ArrayVal(lhs), ValueType::ArrayVal(rhs)) => {
+            let lhs = lhs.borrow();
+            let rhs = rhs.borrow();
+
+            lhs.elements.len() == rhs.elements.len()
+                && lhs
+                    .elements
+                    .iter()
+                    .zip(rhs.elements.iter())
+                    .all(|(lhs, rhs)| deep_equals_inner(lhs, rhs, comparing))
+        }
+        (ValueType::ObjectVal(lhs), ValueType::ObjectVal(rhs)) => {
+            let lhs = lhs.borrow();
+            let rhs = rhs.borrow();
+
+            lhs.properties.len() == rhs.properties.len()
+                && lhs.properties.iter().all(|(key, lhs)| {
+                    rhs.properties
+                        .get(key)
+                        .is_some_and(|rhs| deep_equals_inner(lhs, rhs, comparing))
+                })
+        }
+        _ => false,
+    };
+
+    comparing.pop();
+
+    equal
+}
@@ -1,16 +1,32 @@
-use std::{cell::RefCell, collections::HashMap, error::Error, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, error::Error, rc::Rc};
+
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
 
 use crate::{
     frontend::ast::{
-        AssignmentExpr, BinaryExpr, CallExpr, Identifier, MemberExpr, NodeType, ObjectLiteral,
+        ArrayLiteral, AssignmentExpr, BinaryExpr, CallExpr, FunctionExpr, Identifier, LogicalExpr,
+        MemberExpr, NodeType, ObjectLiteral, TemplateLiteral, UnaryExpr,
     },
     runtime::{
         environment::{Environment, EnvironmentScope},
-        interpreter::evaluate,
-        values::{mk_bool, mk_null, mk_number, ObjectVal, ValueType},
+        errors::with_span,
+        interpreter::{evaluate, execute, Completion},
+        values::{
+            mk_array, mk_bigint, mk_bool, mk_integer, mk_null, mk_number, mk_object, mk_string,
+            ArrayVal, FunctionVal, ValueType,
+        },
     },
 };
 
+use super::{
+    arrays::array_method,
+    equality::{compare, loose_equals, strict_equals},
+    native_fns::match_type,
+    strings::string_method,
+};
+
 pub fn eval_identifier(
     ident: &Identifier,
     env: Rc<RefCell<dyn EnvironmentScope>>,
@@ -20,13 +36,28 @@ pub fn eval_identifier(
     Ok(val)
 }
 
+pub fn eval_template_literal(
+    template: &TemplateLiteral,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let mut res = String::new();
+
+    for (i, quasi) in template.quasis.iter().enumerate() {
+        res += quasi;
+
+        if let Some(expr) = template.expressions.get(i) {
+            res += match_type(evaluate(expr, &env)?).as_str();
+        }
+    }
+
+    Ok(mk_string(res))
+}
+
 pub fn eval_object_expr(
     obj: &ObjectLiteral,
     env: Rc<RefCell<dyn EnvironmentScope>>,
 ) -> Result<ValueType, Box<dyn Error>> {
-    let mut object = ObjectVal {
-        properties: HashMap::new(),
-    };
+    let mut properties = IndexMap::new();
 
     for node_type in obj.properties.clone() {
         let obj_property = match node_type {
@@ -38,56 +69,137 @@ pub fn eval_object_expr(
         };
 
         let runtime_val = match obj_property.value {
-            Some(value) => evaluate(&*value, &env)?,
-            None => env.borrow_mut().lookup_var(obj_property.key.clone())?,
+            Some(value) => evaluate(&value, &env)?,
+            None => env.borrow().lookup_var(obj_property.key.clone())?,
         };
 
-        object.properties.insert(obj_property.key, runtime_val);
+        properties.insert(obj_property.key, runtime_val);
+    }
+
+    Ok(mk_object(properties))
+}
+
+pub fn eval_array_expr(
+    array: &ArrayLiteral,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let mut elements: Vec<ValueType> = vec![];
+
+    for element in &array.elements {
+        elements.push(evaluate(element, &env)?);
     }
 
-    Ok(ValueType::ObjectVal(object))
+    Ok(mk_array(elements))
 }
 
+/// Calling a function stored on an object, like `obj.method()`, makes `obj` its `this`.
 pub fn eval_call_expr(
     expr: &CallExpr,
     env: Rc<RefCell<dyn EnvironmentScope>>,
 ) -> Result<ValueType, Box<dyn Error>> {
-    let args = {
-        let mut args_vec: Vec<ValueType> = vec![];
+    // The callee is evaluated before the arguments, and only once, even for methods.
+    let NodeType::MemberExpr(member_expr) = &*expr.caller else {
+        let callee = evaluate(&expr.caller, &env)?;
+
+        return call_function(callee, eval_args(&expr.args, &env)?, &env);
+    };
 
-        for arg in expr.args.clone() {
-            args_vec.push(evaluate(&arg, &env)?);
+    let object = evaluate(&member_expr.object, &env)?;
+    let key = eval_member_key(member_expr, &env)?;
+
+    if let ValueType::StringVal(name) = &key {
+        match &object {
+            ValueType::ArrayVal(array_val) => {
+                if let Some(method) = array_method(&name.value) {
+                    return method(array_val, eval_args(&expr.args, &env)?, &env);
+                }
+            }
+            ValueType::StringVal(string_val) => {
+                if let Some(method) = string_method(&name.value) {
+                    return method(&string_val.value, eval_args(&expr.args, &env)?);
+                }
+            }
+            _ => {}
         }
+    }
 
-        args_vec
-    };
+    let callee = get_property(&object, &key)?;
+
+    call_with_this(callee, Some(object), eval_args(&expr.args, &env)?, &env)
+}
 
-    let mut function = evaluate(&expr.caller, &env)?;
+fn eval_args(
+    args: &[NodeType],
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<Vec<ValueType>, Box<dyn Error>> {
+    let mut args_vec: Vec<ValueType> = vec![];
 
-    if let ValueType::NativeFnVal(native_fn) = &function {
-        let result = (native_fn.call)(args);
+    for arg in args {
+        args_vec.push(evaluate(arg, env)?);
+    }
+
+    Ok(args_vec)
+}
+
+/// A function expression is a closure over the scope it's evaluated in, the same way a
+/// declared function is.
+pub fn eval_function_expr(
+    function: &FunctionExpr,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    Ok(ValueType::FunctionVal(FunctionVal {
+        name: "anonymous".to_string(),
+        parameters: function.parameters.clone(),
+        declaration_env: env,
+        body: function.body.clone(),
+    }))
+}
 
-        return Ok(result?);
+/// Calls a function value with arguments that have already been evaluated. Parameters
+/// without a matching argument are null.
+pub fn call_function(
+    function: ValueType,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    call_with_this(function, None, args, env)
+}
+
+/// Same as `call_function()`, with `this` declared in the function's scope when there's a
+/// receiver. Without one, `this` is whatever it was where the function was declared.
+fn call_with_this(
+    function: ValueType,
+    this: Option<ValueType>,
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    if let ValueType::NativeFnVal(native_fn) = &function {
+        return (native_fn.call)(args, env);
     }
 
-    if let ValueType::FunctionVal(function_val) = &mut function {
+    if let ValueType::FunctionVal(function_val) = &function {
         let scope: Rc<RefCell<dyn EnvironmentScope>> =
             Environment::new(Some(function_val.declaration_env.clone()));
 
+        if let Some(this) = this {
+            scope.borrow().declare_var("this", this, true)?;
+        }
+
         for (i, value) in function_val.parameters.iter().enumerate() {
-            scope.borrow().declare_var(
-                value,
-                args.get(i)
-                    .expect("Failed to get function arg at dynamic index")
-                    .clone(),
-                false,
-            )?;
+            scope
+                .borrow()
+                .declare_var(value, args.get(i).cloned().unwrap_or(mk_null()), false)?;
         }
 
         let mut result = mk_null();
 
         for stmt in function_val.body.clone() {
-            result = evaluate(&stmt, &scope)?;
+            result = match execute(&stmt, &scope)? {
+                Completion::Return(value) => return Ok(value),
+                completion => completion
+                    .into_value()
+                    .map_err(|err| with_span(err, stmt.span()))?,
+            };
         }
 
         return Ok(result);
@@ -103,8 +215,8 @@ pub fn eval_assignment(
     node: &AssignmentExpr,
     env: Rc<RefCell<dyn EnvironmentScope>>,
 ) -> Result<ValueType, Box<dyn Error>> {
-    if matches!(*node.assign, NodeType::MemberExpr(_)) {
-        return Ok(eval_member_expr(env.clone(), Some(node), None)?);
+    if let NodeType::MemberExpr(member_expr) = &*node.assign {
+        return assign_member(member_expr, &node.value, env);
     }
 
     if !matches!(*node.assign.clone(), NodeType::Identifier(_)) {
@@ -116,9 +228,8 @@ pub fn eval_assignment(
         _ => Err("Varname is not of type NodeType::Identifier")?,
     };
 
-    Ok(env
-        .borrow()
-        .assign_var(varname, evaluate(&node.value, &env)?)?)
+    env.borrow()
+        .assign_var(varname, evaluate(&node.value, &env)?)
 }
 
 pub fn eval_binary_expr(
@@ -128,7 +239,66 @@ pub fn eval_binary_expr(
     let lhs = evaluate(&binop.left, &env)?;
     let rhs = evaluate(&binop.right, &env)?;
 
-    Ok(eval_numeric_binary_expr(lhs, rhs, binop.operator.as_str())?)
+    eval_numeric_binary_expr(lhs, rhs, binop.operator.as_str())
+}
+
+pub fn eval_unary_expr(
+    unary: &UnaryExpr,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let argument = evaluate(&unary.argument, &env)?;
+
+    Ok(match (unary.operator.as_str(), argument) {
+        ("typeof", argument) => mk_string(argument.type_name().to_string()),
+        ("!", ValueType::BooleanVal(boolean_val)) => mk_bool(Some(!boolean_val.value)),
+        ("-", ValueType::IntegerVal(integer_val)) => {
+            mk_integer(Some(integer_val.value.checked_neg().ok_or_else(|| {
+                format!("Integer overflow in -({})", integer_val.value)
+            })?))
+        }
+        ("-", ValueType::NumberVal(number_val)) => mk_number(Some(-number_val.value)),
+        ("-", ValueType::BigIntVal(bigint_val)) => mk_bigint(Some(-bigint_val.value)),
+        (
+            "+",
+            argument @ (ValueType::IntegerVal(_)
+            | ValueType::NumberVal(_)
+            | ValueType::BigIntVal(_)),
+        ) => argument,
+        (operator, argument) => Err(format!(
+            "Cannot apply \"{}\" to a value of type {}",
+            operator,
+            argument.type_name()
+        ))?,
+    })
+}
+
+/// `&&` and `||` decide on their left side first, and only evaluate the right side if the
+/// left one didn't settle it. The left side has to be a boolean, but the right side's value
+/// is returned as-is.
+pub fn eval_logical_expr(
+    logical: &LogicalExpr,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let lhs = match evaluate(&logical.left, &env)? {
+        ValueType::BooleanVal(boolean_val) => boolean_val.value,
+        lhs => Err(format!(
+            "Left-hand side of \"{}\" is not a boolean: {}",
+            logical.operator,
+            match_type(lhs)
+        ))?,
+    };
+
+    let decided = match logical.operator.as_str() {
+        "&&" => !lhs,
+        "||" => lhs,
+        _ => Err("Unknown operator provided in logical operation.")?,
+    };
+
+    if decided {
+        return Ok(mk_bool(Some(lhs)));
+    }
+
+    evaluate(&logical.right, &env)
 }
 
 pub fn eval_numeric_binary_expr(
@@ -137,175 +307,352 @@ pub fn eval_numeric_binary_expr(
     operator: &str,
 ) -> Result<ValueType, Box<dyn Error>> {
     match operator {
-        "!=" => Ok(equals(lhs, rhs, false)?),
-        "==" | "&&" => Ok(equals(lhs, rhs, true)?),
-        "|" => {
-            let llhs = match lhs {
-                ValueType::BooleanVal(boolean_val) => boolean_val,
-                _ => Err("llhs is not of type ValueType::BooleanVal")?,
-            };
-            let rrhs = match rhs {
-                ValueType::BooleanVal(boolean_val) => boolean_val,
-                _ => Err("rrhs is not of type ValueType::BooleanVal")?,
-            };
-
-            Ok(mk_bool(Some(llhs.value || rrhs.value)))
+        "==" => Ok(mk_bool(Some(loose_equals(&lhs, &rhs)))),
+        "!=" => Ok(mk_bool(Some(!loose_equals(&lhs, &rhs)))),
+        "===" => Ok(mk_bool(Some(strict_equals(&lhs, &rhs)))),
+        "!==" => Ok(mk_bool(Some(!strict_equals(&lhs, &rhs)))),
+        // Values that can't be ordered, like a string and a number, are never less or greater.
+        "<" | ">" | "<=" | ">=" => {
+            let ordering = compare(&lhs, &rhs);
+
+            Ok(mk_bool(Some(match operator {
+                "<" => ordering == Some(Ordering::Less),
+                ">" => ordering == Some(Ordering::Greater),
+                "<=" => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                _ => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+            })))
         }
-        _ => {
-            let lhs_is_number_val = matches!(lhs, ValueType::NumberVal(_));
-            let rhs_is_number_val = matches!(rhs, ValueType::NumberVal(_));
-
-            if lhs_is_number_val && rhs_is_number_val {
-                let llhs = match lhs {
-                    ValueType::NumberVal(boolean_val) => boolean_val,
-                    _ => Err("llhs is not of type ValueType::NumberVal")?,
-                };
-                let rrhs = match rhs {
-                    ValueType::NumberVal(boolean_val) => boolean_val,
-                    _ => Err("rrhs is not of type ValueType::NumberVal")?,
+        _ => match (lhs, rhs) {
+            (ValueType::IntegerVal(llhs), ValueType::IntegerVal(rrhs)) => {
+                eval_integer_binary_expr(llhs.value, rrhs.value, operator)
+            }
+            (lhs, rhs)
+                if matches!(lhs, ValueType::BigIntVal(_))
+                    || matches!(rhs, ValueType::BigIntVal(_)) =>
+            {
+                match (as_bigint(&lhs), as_bigint(&rhs)) {
+                    (Some(llhs), Some(rrhs)) => eval_bigint_binary_expr(llhs, rrhs, operator),
+                    _ if as_float(&lhs).is_some() || as_float(&rhs).is_some() => Err(format!(
+                        "Cannot mix BigInt and float values in {} {} {}",
+                        match_type(lhs),
+                        operator,
+                        match_type(rhs)
+                    ))?,
+                    _ => Err(operand_type_error(&lhs, operator, &rhs))?,
+                }
+            }
+            (lhs, rhs) => {
+                let (Some(llhs), Some(rrhs)) = (as_float(&lhs), as_float(&rhs)) else {
+                    Err(operand_type_error(&lhs, operator, &rhs))?
                 };
 
-                return Ok(match operator {
-                    "+" => mk_number(Some(llhs.value + rrhs.value)),
-                    "-" => mk_number(Some(llhs.value - rrhs.value)),
-                    "*" => mk_number(Some(llhs.value * rrhs.value)),
-                    "/" => mk_number(Some(llhs.value / rrhs.value)),
-                    "%" => mk_number(Some(llhs.value % rrhs.value)),
-                    "<" => mk_bool(Some(llhs.value < rrhs.value)),
-                    ">" => mk_bool(Some(llhs.value > rrhs.value)),
-                    _ => Err(format!(
-                        // lhs, rhs
-                        "Unknown operator provided in operation.",
+                Ok(match operator {
+                    "+" => mk_number(Some(llhs + rrhs)),
+                    "-" => mk_number(Some(llhs - rrhs)),
+                    "*" => mk_number(Some(llhs * rrhs)),
+                    "/" => mk_number(Some(llhs / rrhs)),
+                    "%" => mk_number(Some(llhs % rrhs)),
+                    "**" => mk_number(Some(llhs.powf(rrhs))),
+                    "&" | "|" | "^" | "<<" | ">>" => Err(format!(
+                        "Bitwise operators only work on integers, found {} {} {}",
+                        llhs, operator, rrhs
                     ))?,
-                });
+                    _ => Err("Unknown operator provided in operation.")?,
+                })
             }
+        },
+    }
+}
 
-            Ok(mk_null())
-        }
+/// Arithmetic and bitwise operators only work on numbers. Anything else is an error rather
+/// than a quiet `null`, so `true | false` doesn't go unnoticed.
+fn operand_type_error(lhs: &ValueType, operator: &str, rhs: &ValueType) -> String {
+    format!(
+        "Cannot apply \"{}\" to values of type {} and {}",
+        operator,
+        lhs.type_name(),
+        rhs.type_name()
+    )
+}
+
+/// Any number as a float. Integers mixed with floats are promoted to floats.
+fn as_float(value: &ValueType) -> Option<f64> {
+    match value {
+        ValueType::NumberVal(number_val) => Some(number_val.value),
+        ValueType::IntegerVal(integer_val) => Some(integer_val.value as f64),
+        _ => None,
     }
 }
 
-fn equals(lhs: ValueType, rhs: ValueType, strict: bool) -> Result<ValueType, Box<dyn Error>> {
-    match lhs {
-        ValueType::BooleanVal(boolean_val) => {
-            let rhs_bool_val = match rhs {
-                ValueType::BooleanVal(rhs_boolean_val) => rhs_boolean_val,
-                _ => Err("Type of RHS does not match LHS")?,
-            }
-            .value;
+/// BigInts and integers as a BigInt. Floats are left out, since they can't be mixed with
+/// BigInts without losing precision.
+fn as_bigint(value: &ValueType) -> Option<BigInt> {
+    match value {
+        ValueType::BigIntVal(bigint_val) => Some(bigint_val.value.clone()),
+        ValueType::IntegerVal(integer_val) => Some(BigInt::from(integer_val.value)),
+        _ => None,
+    }
+}
 
-            Ok(mk_bool(Some(if strict {
-                boolean_val.value == rhs_bool_val
-            } else {
-                boolean_val.value != rhs_bool_val
-            })))
+/// BigInt arithmetic never overflows. Division truncates towards zero, so `7n / 2n` is `3n`.
+fn eval_bigint_binary_expr(
+    lhs: BigInt,
+    rhs: BigInt,
+    operator: &str,
+) -> Result<ValueType, Box<dyn Error>> {
+    if (operator == "/" || operator == "%") && rhs.is_zero() {
+        Err(format!("Division by zero in {} {} {}", lhs, operator, rhs))?
+    }
+
+    Ok(match operator {
+        "+" => mk_bigint(Some(lhs + rhs)),
+        "-" => mk_bigint(Some(lhs - rhs)),
+        "*" => mk_bigint(Some(lhs * rhs)),
+        "/" => mk_bigint(Some(lhs / rhs)),
+        "%" => mk_bigint(Some(lhs % rhs)),
+        "**" => {
+            let exponent = rhs
+                .to_u32()
+                .ok_or_else(|| format!("Invalid BigInt exponent in {} ** {}", lhs, rhs))?;
+
+            mk_bigint(Some(lhs.pow(exponent)))
         }
-        ValueType::NumberVal(number_val) => {
-            let rhs_num_val = match rhs {
-                ValueType::NumberVal(rhs_number_val) => rhs_number_val,
-                _ => Err("Type of RHS does not match LHS")?,
-            }
-            .value;
+        "<<" | ">>" => {
+            let shift = rhs
+                .to_usize()
+                .ok_or_else(|| format!("Invalid shift amount in {} {} {}", lhs, operator, rhs))?;
 
-            Ok(mk_bool(Some(if strict {
-                number_val.value == rhs_num_val
+            mk_bigint(Some(if operator == "<<" {
+                lhs << shift
             } else {
-                number_val.value != rhs_num_val
-            })))
+                lhs >> shift
+            }))
         }
-        ValueType::StringVal(string_val) => {
-            let rhs_str_val = match rhs {
-                ValueType::StringVal(rhs_string_val) => rhs_string_val,
-                _ => Err("Type of RHS does not match LHS")?,
+        "&" => mk_bigint(Some(lhs & rhs)),
+        "|" => mk_bigint(Some(lhs | rhs)),
+        "^" => mk_bigint(Some(lhs ^ rhs)),
+        _ => Err("Unknown operator provided in operation.")?,
+    })
+}
+
+/// Arithmetic on two integers stays exact, and overflowing is an error rather than a silent
+/// wrap. Division is the exception: it always gives a float, so `7 / 2` is `3.5`, and so does
+/// raising to a negative power.
+fn eval_integer_binary_expr(
+    lhs: i64,
+    rhs: i64,
+    operator: &str,
+) -> Result<ValueType, Box<dyn Error>> {
+    let overflow = || format!("Integer overflow in {} {} {}", lhs, operator, rhs);
+
+    Ok(match operator {
+        "+" => mk_integer(Some(lhs.checked_add(rhs).ok_or_else(overflow)?)),
+        "-" => mk_integer(Some(lhs.checked_sub(rhs).ok_or_else(overflow)?)),
+        "*" => mk_integer(Some(lhs.checked_mul(rhs).ok_or_else(overflow)?)),
+        "/" => mk_number(Some(lhs as f64 / rhs as f64)),
+        "%" => {
+            if rhs == 0 {
+                Err(format!("Division by zero in {} % {}", lhs, rhs))?
             }
-            .value;
 
-            Ok(mk_bool(Some(if strict {
-                string_val.value == rhs_str_val
-            } else {
-                string_val.value != rhs_str_val
-            })))
+            mk_integer(Some(lhs.checked_rem(rhs).ok_or_else(overflow)?))
         }
-        ValueType::FunctionVal(function_val) => {
-            let rhs_fn_val = match rhs {
-                ValueType::FunctionVal(rhs_function_val) => rhs_function_val,
-                _ => Err("Type of RHS does not match LHS")?,
+        "**" => match u32::try_from(rhs) {
+            Ok(exponent) => mk_integer(Some(lhs.checked_pow(exponent).ok_or_else(overflow)?)),
+            Err(_) if rhs < 0 => mk_number(Some((lhs as f64).powf(rhs as f64))),
+            Err(_) => Err(overflow())?,
+        },
+        "<<" | ">>" => {
+            if rhs < 0 {
+                Err(format!(
+                    "Negative shift amount in {} {} {}",
+                    lhs, operator, rhs
+                ))?
             }
-            .body;
 
-            Ok(mk_bool(Some(if strict {
-                function_val.body == rhs_fn_val
+            let shift = rhs.min(63) as u32;
+
+            if operator == ">>" {
+                mk_integer(Some(lhs >> shift))
             } else {
-                function_val.body != rhs_fn_val
-            })))
-        }
-        ValueType::NativeFnVal(native_fn_val) => {
-            let rhs_n_fn_val = match rhs {
-                ValueType::NativeFnVal(rhs_native_fn_val) => rhs_native_fn_val,
-                _ => Err("Type of RHS does not match LHS")?,
+                let shifted = lhs << shift;
+
+                // Bits shifted out of the top are lost, which is overflow like any other.
+                if (rhs > 63 && lhs != 0) || shifted >> shift != lhs {
+                    Err(overflow())?
+                }
+
+                mk_integer(Some(shifted))
             }
-            .call;
+        }
+        "&" => mk_integer(Some(lhs & rhs)),
+        "|" => mk_integer(Some(lhs | rhs)),
+        "^" => mk_integer(Some(lhs ^ rhs)),
+        _ => Err("Unknown operator provided in operation.")?,
+    })
+}
 
-            Ok(mk_bool(Some(if strict {
-                std::ptr::fn_addr_eq(native_fn_val.call, rhs_n_fn_val)
-            } else {
-                !std::ptr::fn_addr_eq(native_fn_val.call, rhs_n_fn_val)
-            })))
+/// Reads `obj.key` or `obj[key]`, however deep it's nested.
+pub fn eval_member_expr(
+    member: &MemberExpr,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let object = evaluate(&member.object, &env)?;
+    let key = eval_member_key(member, &env)?;
+
+    get_property(&object, &key)
+}
+
+/// The key a member expression accesses: the value in brackets for `obj[key]`, or the
+/// property name for `obj.key`.
+fn eval_member_key(
+    expr: &MemberExpr,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    if expr.computed {
+        return evaluate(&expr.property, env);
+    }
+
+    match &*expr.property {
+        NodeType::Identifier(identifier) => Ok(mk_string(identifier.symbol.clone())),
+        _ => Err(format!("'{:?}' is not an Identifier", expr.property))?,
+    }
+}
+
+/// The name of an object property. Integers work too, so `obj[1]` is `obj["1"]`.
+fn property_name(key: &ValueType) -> Result<String, Box<dyn Error>> {
+    match key {
+        ValueType::StringVal(string_val) => Ok(string_val.value.clone()),
+        ValueType::IntegerVal(integer_val) => Ok(integer_val.value.to_string()),
+        _ => Err(format!(
+            "Property key must be a string or an integer, found {}",
+            key.type_name()
+        ))?,
+    }
+}
+
+/// Reads a property of any value. Objects give null for properties they don't have, but
+/// reading anything from null is an error.
+fn get_property(object: &ValueType, key: &ValueType) -> Result<ValueType, Box<dyn Error>> {
+    match object {
+        ValueType::ObjectVal(object_val) => Ok(object_val
+            .borrow()
+            .properties
+            .get(&property_name(key)?)
+            .cloned()
+            .unwrap_or(mk_null())),
+        ValueType::ArrayVal(array_val) => array_member(&array_val.borrow(), key),
+        ValueType::StringVal(string_val) => string_member(&string_val.value, key),
+        _ => Err(format!(
+            "Cannot read property '{}' of {}",
+            match_type(key.clone()),
+            object.type_name()
+        ))?,
+    }
+}
+
+fn array_index(key: &ValueType) -> Result<usize, Box<dyn Error>> {
+    match key {
+        ValueType::IntegerVal(integer_val) if integer_val.value < 0 => {
+            Err(format!("Array index {} is negative", integer_val.value))?
         }
-        ValueType::NullVal => {
-            if !matches!(rhs, ValueType::NullVal) {
-                Err("Type of RHS does not match LHS")?
-            }
+        ValueType::IntegerVal(integer_val) => Ok(integer_val.value as usize),
+        _ => Err(format!(
+            "Array index must be an integer, found {}",
+            match_type(key.clone())
+        ))?,
+    }
+}
 
-            Ok(mk_bool(Some(true)))
+/// Reads an element of an array, or its `length`. Reading past the end gives null.
+fn array_member(array: &ArrayVal, key: &ValueType) -> Result<ValueType, Box<dyn Error>> {
+    if let ValueType::StringVal(string_val) = key {
+        if string_val.value == "length" {
+            return Ok(mk_integer(Some(array.elements.len() as i64)));
         }
-        #[allow(unused_variables)]
-        ValueType::ObjectVal(object_val) => {
-            let rhs_obj_val = match rhs {
-                ValueType::ObjectVal(rhs_object_val) => rhs_object_val,
-                _ => Err("Type of RHS does not match LHS")?,
-            }
-            .properties;
 
-            Ok(mk_bool(Some(if strict {
-                matches!(object_val.properties, rhs_obj_val)
-            } else {
-                !matches!(object_val.properties, rhs_obj_val)
-            })))
+        Err(format!(
+            "Property '{}' does not exist on array",
+            string_val.value
+        ))?
+    }
+
+    Ok(array
+        .elements
+        .get(array_index(key)?)
+        .cloned()
+        .unwrap_or(mk_null()))
+}
+
+/// Strings only have a `length`, counted in characters. Their methods are only there to be
+/// called.
+fn string_member(string: &str, key: &ValueType) -> Result<ValueType, Box<dyn Error>> {
+    match key {
+        ValueType::StringVal(string_val) if string_val.value == "length" => {
+            Ok(mk_integer(Some(string.chars().count() as i64)))
         }
+        _ => Err(format!(
+            "Property '{}' does not exist on string",
+            match_type(key.clone())
+        ))?,
     }
 }
 
-pub fn eval_member_expr(
+/// Assigns to `obj.key` or `obj[key]`. The object and key are evaluated before the value.
+fn assign_member(
+    member: &MemberExpr,
+    value: &NodeType,
     env: Rc<RefCell<dyn EnvironmentScope>>,
-    node: Option<&AssignmentExpr>,
-    expr: Option<&MemberExpr>,
 ) -> Result<ValueType, Box<dyn Error>> {
-    if let Some(expr_val) = expr {
-        let variable = env
+    let object = evaluate(&member.object, &env)?;
+    let key = eval_member_key(member, &env)?;
+    let value = evaluate(value, &env)?;
+
+    set_property(&object, &key, value)
+}
+
+fn set_property(
+    object: &ValueType,
+    key: &ValueType,
+    value: ValueType,
+) -> Result<ValueType, Box<dyn Error>> {
+    let ValueType::ArrayVal(array_val) = object else {
+        let ValueType::ObjectVal(object_val) = object else {
+            Err(format!(
+                "Cannot set property '{}' of {}",
+                match_type(key.clone()),
+                object.type_name()
+            ))?
+        };
+
+        object_val
             .borrow_mut()
-            .lookup_or_mut_object(expr_val.clone(), None, None)?;
+            .properties
+            .insert(property_name(key)?, value.clone());
+
+        return Ok(value);
+    };
 
-        return Ok(variable);
+    if matches!(key, ValueType::StringVal(string_val) if string_val.value == "length") {
+        Err("Cannot assign to 'length' of array")?
     }
 
-    if let Some(node_val) = node {
-        let variable = env.borrow_mut().lookup_or_mut_object(
-            match *node_val.assign.clone() {
-                NodeType::MemberExpr(member_expr) => member_expr,
-                _ => Err(format!(
-                    "'{:?}' is not of type NodeType::MemberExpr",
-                    node_val
-                ))?,
-            },
-            Some(evaluate(&node_val.value, &env)?),
-            None,
-        );
-
-        return variable;
+    let index = array_index(key)?;
+    let elements = &mut array_val.borrow_mut().elements;
+
+    // Writing right after the last element appends to the array. Anything further out would
+    // leave a gap, or try to allocate more memory than there is.
+    if index == elements.len() {
+        elements.push(value.clone());
+    } else if index < elements.len() {
+        elements[index] = value.clone();
+    } else {
+        Err(format!(
+            "Array index {} is out of range for an array of length {}",
+            index,
+            elements.len()
+        ))?
     }
 
-    Err(
-        "Evaluating a member expression is not possible without a member or assignment expression.",
-    )?
+    Ok(value)
 }
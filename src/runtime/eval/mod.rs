@@ -1,3 +1,6 @@
+pub mod arrays;
+pub mod equality;
 pub mod expressions;
 pub mod native_fns;
 pub mod statements;
+pub mod strings;
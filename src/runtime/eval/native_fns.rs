@@ -1,15 +1,26 @@
 use std::{
+    cell::RefCell,
     error::Error,
     io,
     process::Command,
+    rc::Rc,
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use regex::Regex;
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, ToPrimitive};
 
-use crate::runtime::values::{mk_null, mk_number, mk_string, ValueType};
+use crate::runtime::{
+    environment::EnvironmentScope,
+    values::{mk_bigint, mk_bool, mk_integer, mk_null, mk_number, mk_string, ValueType},
+};
+
+use super::equality::deep_equals;
 
-pub fn print_values(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+pub fn print_values(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
     for arg in args {
         println!("{}", match_type(arg));
     }
@@ -18,24 +29,64 @@ pub fn print_values(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
 }
 
 pub fn match_type(arg: ValueType) -> String {
-    match arg {
+    format_value(arg, &mut vec![], 0)
+}
+
+/// Formats a value for printing. `parents` holds the objects and arrays being printed around
+/// this value, so one that contains itself is printed as `[Circular]` instead of forever.
+/// `depth` is how many objects it's inside of, which is how far its properties are indented.
+fn format_value(arg: ValueType, parents: &mut Vec<*const ()>, depth: usize) -> String {
+    let address = match &arg {
+        ValueType::ObjectVal(object_val) => Some(Rc::as_ptr(object_val) as *const ()),
+        ValueType::ArrayVal(array_val) => Some(Rc::as_ptr(array_val) as *const ()),
+        _ => None,
+    };
+
+    if let Some(address) = address {
+        if parents.contains(&address) {
+            return "[Circular]".to_string();
+        }
+
+        parents.push(address);
+    }
+
+    let formatted = match arg {
         ValueType::StringVal(string_val) => string_val.value,
         ValueType::NumberVal(number_val) => number_val.value.to_string(),
+        ValueType::IntegerVal(integer_val) => integer_val.value.to_string(),
+        ValueType::BigIntVal(bigint_val) => bigint_val.value.to_string(),
         ValueType::BooleanVal(boolean_val) => boolean_val.value.to_string(),
         ValueType::NullVal => "null".to_string(),
         ValueType::ObjectVal(object_val) => {
+            let indent = "  ".repeat(depth);
             let mut obj = String::new();
 
             obj += "{\n";
 
-            for (key, value) in object_val.properties.iter() {
-                obj += format!("  {}: {},\n", key, match_type(value.clone())).as_str();
+            for (key, value) in object_val.borrow().properties.iter() {
+                obj += format!(
+                    "{}  {}: {},\n",
+                    indent,
+                    key,
+                    format_value(value.clone(), parents, depth + 1)
+                )
+                .as_str();
             }
 
-            obj += "}";
+            obj += format!("{}}}", indent).as_str();
 
             obj
         }
+        ValueType::ArrayVal(array_val) => {
+            let elements: Vec<String> = array_val
+                .borrow()
+                .elements
+                .iter()
+                .map(|element| format_value(element.clone(), parents, depth))
+                .collect();
+
+            format!("[{}]", elements.join(", "))
+        }
         ValueType::FunctionVal(function_val) => format!(
             "function {{\n  name: {},\n  body: {:?},\n  internal: false\n}}",
             function_val.name, function_val.body
@@ -43,11 +94,20 @@ pub fn match_type(arg: ValueType) -> String {
         ValueType::NativeFnVal(native_fn_val) => {
             format!("function {} {{ [native code] }}", native_fn_val.name)
         }
+    };
+
+    if address.is_some() {
+        parents.pop();
     }
+
+    formatted
 }
 
-pub fn exec(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let cmd = match args.get(0).expect("Command message is required") {
+pub fn exec(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let cmd = match args.first().expect("Command message is required") {
         ValueType::StringVal(string_val) => string_val.value.clone(),
         _ => Err("Command message must be of type StringVal")?,
     };
@@ -63,8 +123,11 @@ pub fn exec(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
     Ok(mk_string(output))
 }
 
-pub fn input(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let cmd = match args.get(0).expect("Command message is required") {
+pub fn input(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let cmd = match args.first().expect("Command message is required") {
         ValueType::StringVal(string_val) => string_val.value.clone(),
         _ => Err("Command message must be of type StringVal")?,
     };
@@ -76,74 +139,175 @@ pub fn input(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
         .read_line(&mut input)
         .expect("Failed to get input");
 
-    Ok(if input == "" {
+    Ok(if input.is_empty() {
         mk_null()
     } else {
         mk_string(input)
     })
 }
 
-pub fn math_sqrt(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let num = match args.get(0).expect("Number required for math.sqrt()") {
-        ValueType::NumberVal(number_val) => number_val.value.clone(),
-        _ => Err("Num must be of type NumberVal")?,
-    };
+/// Reads a number argument as a float, whether it's an integer or not.
+fn number_arg(arg: Option<&ValueType>, missing: &str) -> Result<f64, Box<dyn Error>> {
+    match arg.ok_or(missing)? {
+        ValueType::NumberVal(number_val) => Ok(number_val.value),
+        ValueType::IntegerVal(integer_val) => Ok(integer_val.value as f64),
+        _ => Err("Num must be of type NumberVal or IntegerVal")?,
+    }
+}
 
-    Ok(mk_number(Some(f32::sqrt(num))))
+/// Turns a float that's been rounded into an integer, unless it's too large to be one.
+fn whole_number(num: f64) -> ValueType {
+    if num.is_finite() && num >= i64::MIN as f64 && num < i64::MAX as f64 {
+        mk_integer(Some(num as i64))
+    } else {
+        mk_number(Some(num))
+    }
 }
 
-pub fn math_random(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let num1 = match args
-        .get(0)
-        .expect("First number is required for math.random()")
-    {
-        ValueType::NumberVal(number_val) => number_val.value.clone(),
-        _ => Err("Num must be of type NumberVal")?,
-    };
-    let num2 = match args
-        .get(1)
-        .expect("Second number is required for math.random()")
-    {
-        ValueType::NumberVal(number_val) => number_val.value.clone(),
-        _ => Err("Num must be of type NumberVal")?,
-    };
+pub fn math_sqrt(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let num = number_arg(args.first(), "Number required for math.sqrt()")?;
+
+    Ok(mk_number(Some(f64::sqrt(num))))
+}
+
+pub fn math_random(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let num1 = number_arg(args.first(), "First number is required for math.random()")?;
+    let num2 = number_arg(args.get(1), "Second number is required for math.random()")?;
 
-    let min = f32::ceil(num1);
-    let max = f32::floor(num2);
+    let min = f64::ceil(num1);
+    let max = f64::floor(num2);
 
-    let random = f32::floor(rand::random::<f32>() * (max - min + 1.0) + min);
+    let random = f64::floor(rand::random::<f64>() * (max - min + 1.0) + min);
 
-    Ok(mk_number(Some(random)))
+    Ok(whole_number(random))
 }
 
-pub fn math_round(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let num = match args.get(0).expect("Number required for math.round()") {
-        ValueType::NumberVal(number_val) => number_val.value.clone(),
-        _ => Err("Num must be of type NumberVal")?,
-    };
+pub fn math_round(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    if let Some(ValueType::IntegerVal(integer_val)) = args.first() {
+        return Ok(mk_integer(Some(integer_val.value)));
+    }
+
+    let num = number_arg(args.first(), "Number required for math.round()")?;
 
-    Ok(mk_number(Some(f32::round(num))))
+    Ok(whole_number(f64::round(num)))
 }
 
-pub fn math_ceil(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let num = match args.get(0).expect("Number required for math.ceil()") {
-        ValueType::NumberVal(number_val) => number_val.value.clone(),
-        _ => Err("Num must be of type NumberVal")?,
-    };
+pub fn math_ceil(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    if let Some(ValueType::IntegerVal(integer_val)) = args.first() {
+        return Ok(mk_integer(Some(integer_val.value)));
+    }
+
+    let num = number_arg(args.first(), "Number required for math.ceil()")?;
+
+    Ok(whole_number(f64::ceil(num)))
+}
+
+pub fn math_abs(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    if let Some(ValueType::IntegerVal(integer_val)) = args.first() {
+        let abs = integer_val
+            .value
+            .checked_abs()
+            .ok_or("Integer overflow in math.abs()")?;
+
+        return Ok(mk_integer(Some(abs)));
+    }
 
-    Ok(mk_number(Some(f32::ceil(num))))
+    let num = number_arg(args.first(), "Number required for math.abs()")?;
+
+    Ok(mk_number(Some(f64::abs(num))))
 }
 
-pub fn math_abs(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let num = match args.get(0).expect("Number required for math.abs()") {
-        ValueType::NumberVal(number_val) => number_val.value.clone(),
-        _ => Err("Num must be of type NumberVal")?,
+pub fn bigint(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let value = match args.first().ok_or("Value required for bigint()")? {
+        ValueType::BigIntVal(bigint_val) => bigint_val.value.clone(),
+        ValueType::IntegerVal(integer_val) => BigInt::from(integer_val.value),
+        ValueType::NumberVal(number_val) => {
+            if number_val.value.fract() != 0.0 {
+                Err(format!(
+                    "Cannot convert {} to a BigInt, it isn't a whole number",
+                    number_val.value
+                ))?
+            }
+
+            BigInt::from_f64(number_val.value)
+                .ok_or(format!("Cannot convert {} to a BigInt", number_val.value))?
+        }
+        ValueType::StringVal(string_val) => string_val
+            .value
+            .trim()
+            .parse::<BigInt>()
+            .map_err(|_| format!("Cannot convert '{}' to a BigInt", string_val.value))?,
+        _ => Err("Value must be of type BigIntVal, IntegerVal, NumberVal or StringVal")?,
     };
 
-    Ok(mk_number(Some(f32::abs(num))))
+    Ok(mk_bigint(Some(value)))
 }
 
-pub fn strcon(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+/// Converts a value to a number. BigInts that don't fit in an integer lose precision.
+pub fn number(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    Ok(match args.first().ok_or("Value required for number()")? {
+        ValueType::BigIntVal(bigint_val) => match bigint_val.value.to_i64() {
+            Some(integer) => mk_integer(Some(integer)),
+            None => mk_number(bigint_val.value.to_f64()),
+        },
+        ValueType::IntegerVal(integer_val) => mk_integer(Some(integer_val.value)),
+        ValueType::NumberVal(number_val) => mk_number(Some(number_val.value)),
+        ValueType::StringVal(string_val) => {
+            let str = string_val.value.trim();
+
+            match str.parse::<i64>() {
+                Ok(integer) => mk_integer(Some(integer)),
+                Err(_) => mk_number(Some(
+                    str.parse::<f64>()
+                        .map_err(|_| format!("Cannot convert '{}' to a number", str))?,
+                )),
+            }
+        }
+        _ => Err("Value must be of type BigIntVal, IntegerVal, NumberVal or StringVal")?,
+    })
+}
+
+/// Compares two values by their contents, so `deepEqual([1, 2], [1, 2])` is true even though
+/// they're different arrays.
+pub fn deep_equal(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let lhs = args
+        .first()
+        .ok_or("Two values are required for deepEqual()")?;
+    let rhs = args
+        .get(1)
+        .ok_or("Two values are required for deepEqual()")?;
+
+    Ok(mk_bool(Some(deep_equals(lhs, rhs))))
+}
+
+pub fn strcon(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
     let mut res = String::new();
 
     for arg in args {
@@ -155,27 +319,38 @@ pub fn strcon(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
     Ok(mk_string(res))
 }
 
-pub fn format(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
-    let mut_args = &mut args.clone();
-    let str = match_type(mut_args.remove(0));
+pub fn format(
+    args: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    let mut args = args.into_iter();
+    let template = match_type(args.next().ok_or("1st parameter in format! missing.")?);
 
-    let mut res: String = String::new();
+    if args.len() == 0 {
+        Err("2nd parameter in format! missing.")?
+    }
 
-    for arg in args {
-        let arg_str = match_type(arg);
+    // Every argument fills the next `${}` in line. The holes are all filled in one go, so a
+    // `${}` that an argument brings along is left alone.
+    let mut parts = template.split("${}");
+    let mut res = parts.next().unwrap_or_default().to_string();
 
-        let re = Regex::new(r"\$\{\}").expect("Invalid regex pattern");
-        res = re.replace(str.as_str(), arg_str).to_string();
-    }
+    for part in parts {
+        match args.next() {
+            Some(arg) => res += match_type(arg).as_str(),
+            None => res += "${}",
+        }
 
-    if matches!(mut_args.get(0), None) {
-        Err("2nd parameter in format! missing.")?
+        res += part;
     }
 
     Ok(mk_string(res))
 }
 
-pub fn time_function(_: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+pub fn time_function(
+    _: Vec<ValueType>,
+    _: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
     let current_time = SystemTime::now();
 
     // Calculate the number of seconds since the Unix epoch
@@ -184,5 +359,5 @@ pub fn time_function(_: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
         .expect("Time went backwards")
         .as_secs();
 
-    Ok(mk_number(Some(epoch_time as f32)))
+    Ok(mk_integer(Some(epoch_time as i64)))
 }
@@ -2,12 +2,13 @@ use std::{cell::RefCell, error::Error, rc::Rc};
 
 use crate::{
     frontend::ast::{
-        ForStatement, FunctionDeclaration, IfStatement, NodeType, Program, TryCatchStatement,
-        VarDeclaration,
+        BlockStatement, DoWhileStatement, ForStatement, FunctionDeclaration, IfStatement, NodeType,
+        Program, TryCatchStatement, VarDeclaration, WhileStatement,
     },
     runtime::{
         environment::{Environment, EnvironmentScope},
-        interpreter::evaluate,
+        errors::{error_message, with_span},
+        interpreter::{evaluate, execute, Completion},
         values::{mk_null, mk_string, FunctionVal, ValueType},
     },
 };
@@ -21,7 +22,9 @@ pub fn eval_program(
     let mut last_evaluated = mk_null();
 
     for statement in &program.body {
-        last_evaluated = evaluate(statement, &env)?;
+        last_evaluated = execute(statement, &env)?
+            .into_value()
+            .map_err(|err| with_span(err, statement.span()))?;
     }
 
     Ok(last_evaluated)
@@ -30,7 +33,7 @@ pub fn eval_program(
 pub fn eval_if_statement(
     declaration: &IfStatement,
     env: Rc<RefCell<dyn EnvironmentScope>>,
-) -> Result<ValueType, Box<dyn Error>> {
+) -> Result<Completion, Box<dyn Error>> {
     let test = evaluate(&declaration.test, &env)?;
     let test_is_bool = match test {
         ValueType::BooleanVal(boolean_val) => boolean_val.value,
@@ -42,15 +45,23 @@ pub fn eval_if_statement(
     } else if let Some(alternate) = &declaration.alternate {
         eval_body(alternate, env, true)?
     } else {
-        mk_null()
+        Completion::Normal(mk_null())
     })
 }
 
+pub fn eval_block_statement(
+    block: &BlockStatement,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<Completion, Box<dyn Error>> {
+    eval_body(&block.body, env, true)
+}
+
+/// Runs the statements of a block, stopping early at a `break`, `continue` or `return`.
 fn eval_body(
     body: &Vec<NodeType>,
     env: Rc<RefCell<dyn EnvironmentScope>>,
     new_env: bool,
-) -> Result<ValueType, Box<dyn Error>> {
+) -> Result<Completion, Box<dyn Error>> {
     let scope = if new_env {
         let binding: Rc<RefCell<dyn EnvironmentScope>> = Environment::new(Some(env.clone()));
         binding
@@ -61,10 +72,46 @@ fn eval_body(
     let mut result = mk_null();
 
     for stmt in body {
-        result = evaluate(stmt, &scope)?;
+        match execute(stmt, &scope)? {
+            Completion::Normal(value) => result = value,
+            completion => return Ok(completion),
+        }
     }
 
-    Ok(result)
+    Ok(Completion::Normal(result))
+}
+
+/// Runs one iteration of a loop's body. Returns what the loop finished with if the body
+/// ended it: `Normal` if it broke out of this loop, or the completion itself if it's aimed at
+/// a loop further out.
+fn eval_loop_body(
+    body: &Vec<NodeType>,
+    label: &Option<String>,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<Option<Completion>, Box<dyn Error>> {
+    Ok(match eval_body(body, env, true)? {
+        Completion::Normal(_) | Completion::Continue(None) => None,
+        Completion::Continue(Some(target)) if Some(&target) == label.as_ref() => None,
+        Completion::Break(None) => Some(Completion::Normal(mk_null())),
+        Completion::Break(Some(target)) if Some(&target) == label.as_ref() => {
+            Some(Completion::Normal(mk_null()))
+        }
+        completion => Some(completion),
+    })
+}
+
+fn eval_loop_test(
+    test: &NodeType,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+    statement: &str,
+) -> Result<bool, Box<dyn Error>> {
+    match evaluate(test, env)? {
+        ValueType::BooleanVal(boolean_val) => Ok(boolean_val.value),
+        _ => Err(format!(
+            "Test condition of {}() is not of type ValueType::BooleanVal",
+            statement
+        ))?,
+    }
 }
 
 pub fn eval_val_declaration(
@@ -77,70 +124,90 @@ pub fn eval_val_declaration(
         mk_null()
     };
 
-    Ok(env
-        .borrow()
-        .declare_var(&declaration.identifier, value, declaration.constant)?)
+    env.borrow()
+        .declare_var(&declaration.identifier, value, declaration.constant)
 }
 
 pub fn eval_for_statement(
     declaration: &ForStatement,
     env: Rc<RefCell<dyn EnvironmentScope>>,
-) -> Result<ValueType, Box<dyn Error>> {
-    let n_env: Rc<RefCell<dyn EnvironmentScope>> = Environment::new(Some(env.clone()));
-
-    eval_val_declaration(
-        &if let NodeType::VarDeclaration(var_declaration) = &*declaration.init {
-            var_declaration.clone()
-        } else {
-            Err(format!(
-                "'{:?}' is not of type NodeType::VarDeclaration",
-                declaration.init
-            ))?
-        },
-        n_env.clone(),
-    )?;
-
-    let body = declaration.body.clone();
+) -> Result<Completion, Box<dyn Error>> {
+    let mut n_env: Rc<RefCell<dyn EnvironmentScope>> = Environment::new(Some(env.clone()));
+
+    let init = if let NodeType::VarDeclaration(var_declaration) = &*declaration.init {
+        var_declaration
+    } else {
+        Err(format!(
+            "'{:?}' is not of type NodeType::VarDeclaration",
+            declaration.init
+        ))?
+    };
+
+    eval_val_declaration(init, n_env.clone())?;
+
     let update = match *declaration.update.clone() {
         NodeType::AssignmentExpr(assignment_expr) => assignment_expr,
         _ => Err("update of for() is not of type NodeType::AssignmentExpr")?,
     };
 
-    let test_vtype = evaluate(&*declaration.test, &n_env)?;
-    let mut test = match &test_vtype {
-        ValueType::BooleanVal(boolean_val) => boolean_val,
-        _ => Err("Test condition of for() is not of type ValueType::BooleanVal")?,
-    };
+    // The body runs first and the update after it, so the first iteration sees the initial value.
+    while eval_loop_test(&declaration.test, &n_env, "for")? {
+        if let Some(completion) =
+            eval_loop_body(&declaration.body, &declaration.label, n_env.clone())?
+        {
+            return Ok(completion);
+        }
 
-    if !test.value {
-        return Ok(mk_null());
-    }
+        // Every iteration gets its own copy of the loop variable, so closures made in the body
+        // keep the value it had when they were made.
+        let value = n_env.borrow().lookup_var(init.identifier.clone())?;
+        n_env = Environment::new(Some(env.clone()));
+        n_env
+            .borrow()
+            .declare_var(&init.identifier, value, init.constant)?;
 
-    loop {
         eval_assignment(&update, n_env.clone())?;
+    }
 
-        let body_to_eval: Rc<RefCell<dyn EnvironmentScope>> = Environment::new(Some(n_env.clone()));
+    Ok(Completion::Normal(mk_null()))
+}
 
-        eval_body(&body, body_to_eval, false)?;
+pub fn eval_while_statement(
+    declaration: &WhileStatement,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<Completion, Box<dyn Error>> {
+    while eval_loop_test(&declaration.test, &env, "while")? {
+        if let Some(completion) =
+            eval_loop_body(&declaration.body, &declaration.label, env.clone())?
+        {
+            return Ok(completion);
+        }
+    }
 
-        let test_loop_vtype = evaluate(&*declaration.test, &n_env)?;
-        test = match &test_loop_vtype {
-            ValueType::BooleanVal(boolean_val) => boolean_val,
-            _ => Err("Test condition of for() is not of type ValueType::BooleanVal")?,
-        };
+    Ok(Completion::Normal(mk_null()))
+}
 
-        if !test.value {
-            break;
+pub fn eval_do_while_statement(
+    declaration: &DoWhileStatement,
+    env: Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<Completion, Box<dyn Error>> {
+    loop {
+        if let Some(completion) =
+            eval_loop_body(&declaration.body, &declaration.label, env.clone())?
+        {
+            return Ok(completion);
         }
-    }
 
-    Ok(mk_null())
+        if !eval_loop_test(&declaration.test, &env, "do while")? {
+            return Ok(Completion::Normal(mk_null()));
+        }
+    }
 }
 
 pub fn eval_try_catch_statement(
     declaration: &TryCatchStatement,
     env: Rc<RefCell<dyn EnvironmentScope>>,
-) -> Result<ValueType, Box<dyn Error>> {
+) -> Result<Completion, Box<dyn Error>> {
     let try_env: Rc<RefCell<dyn EnvironmentScope>> = Environment::new(Some(env.clone()));
 
     Ok(match eval_body(&declaration.body, try_env, false) {
@@ -148,7 +215,7 @@ pub fn eval_try_catch_statement(
         Err(err) => {
             let catch_env: Rc<RefCell<dyn EnvironmentScope>> = Environment::new(Some(env.clone()));
             env.borrow()
-                .assign_var("error".to_string(), mk_string(err.to_string()))?;
+                .assign_var("error".to_string(), mk_string(error_message(&*err)))?;
             eval_body(&declaration.alternate, catch_env, false)?
         }
     })
@@ -0,0 +1,152 @@
+use std::error::Error;
+
+use crate::runtime::values::{mk_array, mk_bool, mk_integer, mk_string, ValueType};
+
+use super::{
+    arrays::{integer_arg, relative_index},
+    native_fns::match_type,
+};
+
+/// A method on strings. Strings can't be changed, so these all give back something new.
+/// Indexes count characters, not bytes.
+pub type StringMethod = fn(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>>;
+
+const STRING_METHODS: &[(&str, StringMethod); 11] = &[
+    ("upper", upper),
+    ("lower", lower),
+    ("trim", trim),
+    ("split", split),
+    ("includes", includes),
+    ("startsWith", starts_with),
+    ("endsWith", ends_with),
+    ("indexOf", index_of),
+    ("slice", slice),
+    ("replace", replace),
+    ("repeat", repeat),
+];
+
+/// The longest string `repeat()` will build, in bytes. Anything longer is almost certainly a
+/// mistake, and would take the whole interpreter down when it runs out of memory.
+const MAX_REPEAT_LEN: usize = 1 << 28;
+
+pub fn string_method(name: &str) -> Option<StringMethod> {
+    STRING_METHODS
+        .iter()
+        .find(|(method, _)| *method == name)
+        .map(|(_, call)| *call)
+}
+
+fn string_arg(arg: Option<&ValueType>, method: &str) -> Result<String, Box<dyn Error>> {
+    match arg {
+        Some(ValueType::StringVal(string_val)) => Ok(string_val.value.clone()),
+        Some(arg) => Err(format!(
+            "Argument passed to {}() must be a string, found {}",
+            method,
+            match_type(arg.clone())
+        ))?,
+        None => Err(format!("{}() requires a string", method))?,
+    }
+}
+
+fn upper(string: &str, _: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    Ok(mk_string(string.to_uppercase()))
+}
+
+fn lower(string: &str, _: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    Ok(mk_string(string.to_lowercase()))
+}
+
+fn trim(string: &str, _: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    Ok(mk_string(string.trim().to_string()))
+}
+
+/// Splitting on `""` gives every character on its own.
+fn split(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let separator = string_arg(args.first(), "split")?;
+
+    let parts: Vec<ValueType> = if separator.is_empty() {
+        string
+            .chars()
+            .map(|char| mk_string(char.to_string()))
+            .collect()
+    } else {
+        string
+            .split(separator.as_str())
+            .map(|part| mk_string(part.to_string()))
+            .collect()
+    };
+
+    Ok(mk_array(parts))
+}
+
+fn includes(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let search = string_arg(args.first(), "includes")?;
+
+    Ok(mk_bool(Some(string.contains(search.as_str()))))
+}
+
+fn starts_with(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let search = string_arg(args.first(), "startsWith")?;
+
+    Ok(mk_bool(Some(string.starts_with(search.as_str()))))
+}
+
+fn ends_with(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let search = string_arg(args.first(), "endsWith")?;
+
+    Ok(mk_bool(Some(string.ends_with(search.as_str()))))
+}
+
+fn index_of(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let search = string_arg(args.first(), "indexOf")?;
+
+    // `find()` gives a byte offset, which is turned back into a character index.
+    let index = match string.find(search.as_str()) {
+        Some(offset) => string[..offset].chars().count() as i64,
+        None => -1,
+    };
+
+    Ok(mk_integer(Some(index)))
+}
+
+fn slice(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let chars: Vec<char> = string.chars().collect();
+    let len = chars.len();
+
+    let start = relative_index(integer_arg(args.first(), "slice")?.unwrap_or(0), len);
+    let end = relative_index(
+        integer_arg(args.get(1), "slice")?.unwrap_or(len as i64),
+        len,
+    );
+
+    Ok(mk_string(if start < end {
+        chars[start..end].iter().collect()
+    } else {
+        String::new()
+    }))
+}
+
+/// Only replaces the first match, like JS does.
+fn replace(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let from = string_arg(args.first(), "replace")?;
+    let to = string_arg(args.get(1), "replace")?;
+
+    Ok(mk_string(string.replacen(from.as_str(), to.as_str(), 1)))
+}
+
+fn repeat(string: &str, args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>> {
+    let count = match args.first() {
+        Some(ValueType::IntegerVal(integer_val)) if integer_val.value >= 0 => {
+            integer_val.value as usize
+        }
+        _ => Err("repeat() requires a count that is a non-negative integer")?,
+    };
+
+    match string.len().checked_mul(count) {
+        Some(len) if len <= MAX_REPEAT_LEN => Ok(mk_string(string.repeat(count))),
+        _ => Err(format!(
+            "repeat() would make a string longer than {} bytes",
+            MAX_REPEAT_LEN
+        ))?,
+    }
+}
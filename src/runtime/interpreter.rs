@@ -4,20 +4,99 @@ use crate::frontend::ast::NodeType;
 
 use super::{
     environment::EnvironmentScope,
+    errors::with_span,
     eval::{
         expressions::{
-            eval_assignment, eval_binary_expr, eval_call_expr, eval_identifier, eval_member_expr,
-            eval_object_expr,
+            eval_array_expr, eval_assignment, eval_binary_expr, eval_call_expr, eval_function_expr,
+            eval_identifier, eval_logical_expr, eval_member_expr, eval_object_expr,
+            eval_template_literal, eval_unary_expr,
         },
         statements::{
-            eval_for_statement, eval_function_declaration, eval_if_statement, eval_program,
-            eval_try_catch_statement, eval_val_declaration,
+            eval_block_statement, eval_do_while_statement, eval_for_statement,
+            eval_function_declaration, eval_if_statement, eval_program, eval_try_catch_statement,
+            eval_val_declaration, eval_while_statement,
         },
     },
-    values::{NumberVal, StringVal, ValueType},
+    values::{mk_null, BigIntVal, IntegerVal, NumberVal, StringVal, ValueType},
 };
 
-pub fn evaluate<'a>(
+/// How a statement finished running. Anything but `Normal` unwinds through the enclosing
+/// statements until a loop or function call handles it.
+#[derive(Debug, Clone)]
+pub enum Completion {
+    Normal(ValueType),
+    /// `break`, with the label of the loop to break out of if it was given one.
+    Break(Option<String>),
+    Continue(Option<String>),
+    /// `return`, which unwinds all the way to the function being called.
+    Return(ValueType),
+}
+
+impl Completion {
+    /// The value of a statement that finished normally. The parser makes sure `break`,
+    /// `continue` and `return` only appear where they can be handled, which keeps them out of
+    /// a `try` used as a value, so the errors are a last line of defence.
+    pub fn into_value(self) -> Result<ValueType, Box<dyn Error>> {
+        match self {
+            Completion::Normal(value) => Ok(value),
+            Completion::Break(_) => Err("\"break\" can only be used inside a loop.")?,
+            Completion::Continue(_) => Err("\"continue\" can only be used inside a loop.")?,
+            Completion::Return(_) => Err("\"return\" can only be used inside a function.")?,
+        }
+    }
+}
+
+/// Runs a statement, passing on any `break`, `continue` or `return` it ends with.
+pub fn execute(
+    ast_node: &NodeType,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<Completion, Box<dyn Error>> {
+    execute_node(ast_node, env).map_err(|err| with_span(err, ast_node.span()))
+}
+
+fn execute_node(
+    ast_node: &NodeType,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<Completion, Box<dyn Error>> {
+    match ast_node {
+        NodeType::IfStatement(if_statement) => eval_if_statement(if_statement, env.clone()),
+        NodeType::BlockStatement(block_statement) => {
+            eval_block_statement(block_statement, env.clone())
+        }
+        NodeType::ForStatement(for_statement) => eval_for_statement(for_statement, env.clone()),
+        NodeType::WhileStatement(while_statement) => {
+            eval_while_statement(while_statement, env.clone())
+        }
+        NodeType::DoWhileStatement(do_while_statement) => {
+            eval_do_while_statement(do_while_statement, env.clone())
+        }
+        NodeType::TryCatchStatement(try_catch_statement) => {
+            eval_try_catch_statement(try_catch_statement, env.clone())
+        }
+        NodeType::BreakStatement(break_statement) => {
+            Ok(Completion::Break(break_statement.label.clone()))
+        }
+        NodeType::ContinueStatement(continue_statement) => {
+            Ok(Completion::Continue(continue_statement.label.clone()))
+        }
+        NodeType::ReturnStatement(return_statement) => {
+            Ok(Completion::Return(match &return_statement.argument {
+                Some(argument) => evaluate(argument, env)?,
+                None => mk_null(),
+            }))
+        }
+        _ => Ok(Completion::Normal(evaluate_node(ast_node, env)?)),
+    }
+}
+
+pub fn evaluate(
+    ast_node: &NodeType,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>> {
+    evaluate_node(ast_node, env).map_err(|err| with_span(err, ast_node.span()))
+}
+
+fn evaluate_node(
     ast_node: &NodeType,
     env: &Rc<RefCell<dyn EnvironmentScope>>,
 ) -> Result<ValueType, Box<dyn Error>> {
@@ -25,21 +104,38 @@ pub fn evaluate<'a>(
         NodeType::NumericLiteral(numeric_literal) => Ok(ValueType::NumberVal(NumberVal {
             value: numeric_literal.value,
         })),
+        NodeType::IntegerLiteral(integer_literal) => Ok(ValueType::IntegerVal(IntegerVal {
+            value: integer_literal.value,
+        })),
+        NodeType::BigIntLiteral(bigint_literal) => Ok(ValueType::BigIntVal(BigIntVal {
+            value: bigint_literal.value.clone(),
+        })),
         NodeType::StringLiteral(string_literal) => Ok(ValueType::StringVal(StringVal {
             value: string_literal.value.clone(),
         })),
+        NodeType::TemplateLiteral(template_literal) => {
+            eval_template_literal(template_literal, env.clone())
+        }
         NodeType::Identifier(identifier) => eval_identifier(identifier, env.clone()),
         NodeType::ObjectLiteral(object_literal) => eval_object_expr(object_literal, env.clone()),
+        NodeType::ArrayLiteral(array_literal) => eval_array_expr(array_literal, env.clone()),
         NodeType::CallExpr(call_expr) => eval_call_expr(call_expr, env.clone()),
+        NodeType::FunctionExpr(function_expr) => eval_function_expr(function_expr, env.clone()),
         NodeType::AssignmentExpr(assignment_expr) => eval_assignment(assignment_expr, env.clone()),
         NodeType::BinaryExpr(binary_expr) => eval_binary_expr(binary_expr, env.clone()),
+        NodeType::LogicalExpr(logical_expr) => eval_logical_expr(logical_expr, env.clone()),
+        NodeType::UnaryExpr(unary_expr) => eval_unary_expr(unary_expr, env.clone()),
         NodeType::Program(program) => eval_program(program, env.clone()),
-        NodeType::IfStatement(if_statement) => eval_if_statement(if_statement, env.clone()),
-        NodeType::ForStatement(for_statement) => eval_for_statement(for_statement, env.clone()),
-        NodeType::MemberExpr(member_expr) => eval_member_expr(env.clone(), None, Some(member_expr)),
-        NodeType::TryCatchStatement(try_catch_statement) => {
-            eval_try_catch_statement(try_catch_statement, env.clone())
-        }
+        NodeType::MemberExpr(member_expr) => eval_member_expr(member_expr, env.clone()),
+        NodeType::IfStatement(_)
+        | NodeType::BlockStatement(_)
+        | NodeType::ForStatement(_)
+        | NodeType::WhileStatement(_)
+        | NodeType::DoWhileStatement(_)
+        | NodeType::TryCatchStatement(_)
+        | NodeType::BreakStatement(_)
+        | NodeType::ContinueStatement(_)
+        | NodeType::ReturnStatement(_) => execute_node(ast_node, env)?.into_value(),
         NodeType::VarDeclaration(var_declaration) => {
             eval_val_declaration(var_declaration, env.clone())
         }
@@ -1,4 +1,5 @@
 pub mod environment;
+pub mod errors;
 pub mod eval;
 pub mod interpreter;
 pub mod values;
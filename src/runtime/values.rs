@@ -1,20 +1,46 @@
-use std::{cell::RefCell, collections::HashMap, error::Error, rc::Rc};
+use std::{cell::RefCell, error::Error, rc::Rc};
+
+use indexmap::IndexMap;
+
+use num_bigint::BigInt;
 
 use crate::frontend::ast::NodeType;
 
 use super::environment::EnvironmentScope;
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum ValueType {
     NullVal,
     BooleanVal(BooleanVal),
     NumberVal(NumberVal),
+    IntegerVal(IntegerVal),
+    BigIntVal(BigIntVal),
     StringVal(StringVal),
-    ObjectVal(ObjectVal),
+    /// Objects and arrays are shared handles, so copying one around still points at the same
+    /// object, the same way it does in JavaScript.
+    ObjectVal(Rc<RefCell<ObjectVal>>),
+    ArrayVal(Rc<RefCell<ArrayVal>>),
     FunctionVal(FunctionVal),
     NativeFnVal(NativeFnVal),
 }
 
+impl ValueType {
+    /// The name `typeof` gives the value. Integers and floats are both just numbers here.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ValueType::NullVal => "null",
+            ValueType::BooleanVal(_) => "boolean",
+            ValueType::NumberVal(_) | ValueType::IntegerVal(_) => "number",
+            ValueType::BigIntVal(_) => "bigint",
+            ValueType::StringVal(_) => "string",
+            ValueType::ObjectVal(_) => "object",
+            ValueType::ArrayVal(_) => "array",
+            ValueType::FunctionVal(_) | ValueType::NativeFnVal(_) => "function",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BooleanVal {
     pub value: bool,
@@ -22,7 +48,20 @@ pub struct BooleanVal {
 
 #[derive(Debug, Clone)]
 pub struct NumberVal {
-    pub value: f32,
+    pub value: f64,
+}
+
+/// An exact 64-bit integer. Integer literals evaluate to these, and they stay integers
+/// through arithmetic until they meet a float.
+#[derive(Debug, Clone)]
+pub struct IntegerVal {
+    pub value: i64,
+}
+
+/// An integer of any size, written as `123n`. Unlike `IntegerVal`, these never overflow.
+#[derive(Debug, Clone)]
+pub struct BigIntVal {
+    pub value: BigInt,
 }
 
 #[derive(Debug, Clone)]
@@ -30,9 +69,16 @@ pub struct StringVal {
     pub value: String,
 }
 
+/// Properties stay in the order they were first added, so printing an object always gives
+/// the same output.
 #[derive(Debug, Clone)]
 pub struct ObjectVal {
-    pub properties: HashMap<String, ValueType>,
+    pub properties: IndexMap<String, ValueType>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayVal {
+    pub elements: Vec<ValueType>,
 }
 
 #[derive(Debug)]
@@ -54,7 +100,12 @@ impl Clone for FunctionVal {
     }
 }
 
-pub type FunctionCall = fn(args: Vec<ValueType>) -> Result<ValueType, Box<dyn Error>>;
+/// A built-in function. It gets the scope it was called from, so it can call back into
+/// functions written in bussin.
+pub type FunctionCall = fn(
+    args: Vec<ValueType>,
+    env: &Rc<RefCell<dyn EnvironmentScope>>,
+) -> Result<ValueType, Box<dyn Error>>;
 
 #[derive(Debug, Clone)]
 pub struct NativeFnVal {
@@ -69,12 +120,24 @@ pub fn mk_native_fn(name: &str, call: FunctionCall) -> ValueType {
     })
 }
 
-pub fn mk_number(n: Option<f32>) -> ValueType {
+pub fn mk_number(n: Option<f64>) -> ValueType {
     ValueType::NumberVal(NumberVal {
         value: n.unwrap_or_default(),
     })
 }
 
+pub fn mk_integer(n: Option<i64>) -> ValueType {
+    ValueType::IntegerVal(IntegerVal {
+        value: n.unwrap_or_default(),
+    })
+}
+
+pub fn mk_bigint(n: Option<BigInt>) -> ValueType {
+    ValueType::BigIntVal(BigIntVal {
+        value: n.unwrap_or_default(),
+    })
+}
+
 pub fn mk_null() -> ValueType {
     ValueType::NullVal
 }
@@ -89,6 +152,10 @@ pub fn mk_string(value: String) -> ValueType {
     ValueType::StringVal(StringVal { value })
 }
 
-pub fn mk_object(obj: HashMap<String, ValueType>) -> ValueType {
-    ValueType::ObjectVal(ObjectVal { properties: obj })
+pub fn mk_array(elements: Vec<ValueType>) -> ValueType {
+    ValueType::ArrayVal(Rc::new(RefCell::new(ArrayVal { elements })))
+}
+
+pub fn mk_object(obj: IndexMap<String, ValueType>) -> ValueType {
+    ValueType::ObjectVal(Rc::new(RefCell::new(ObjectVal { properties: obj })))
 }
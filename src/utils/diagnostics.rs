@@ -0,0 +1,291 @@
+use std::error::Error;
+
+use crate::{
+    frontend::{
+        errors::{LexError, ParseError},
+        span::Span,
+    },
+    runtime::errors::RuntimeError,
+};
+
+use super::transcriber::SourceMap;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// The code a diagnostic points into. For `.bsx` files, `text` is the original BSX code and
+/// `map` translates spans in the transcribed code back into it.
+pub struct SourceFile {
+    pub name: String,
+    pub text: String,
+    pub map: Option<SourceMap>,
+}
+
+impl SourceFile {
+    pub fn new(name: &str, text: String, map: Option<SourceMap>) -> Self {
+        SourceFile {
+            name: name.to_string(),
+            text,
+            map,
+        }
+    }
+
+    fn original_offset(&self, offset: usize) -> usize {
+        let offset = match &self.map {
+            Some(map) => map.original_offset(offset),
+            None => offset,
+        };
+
+        offset.min(self.text.len())
+    }
+
+    /// The 1-based line and column of a byte offset.
+    fn line_col(&self, offset: usize) -> (usize, usize) {
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        (line, before[line_start..].chars().count() + 1)
+    }
+}
+
+/// An error report in the style of rustc: a message, the offending source line with the
+/// span underlined, and any notes or help that go with it.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Option<Span>,
+    /// Printed next to the underline.
+    pub label: Option<String>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: String, span: Option<Span>) -> Self {
+        Diagnostic {
+            message,
+            span,
+            label: None,
+            notes: vec![],
+            help: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    pub fn render(&self, source: &SourceFile, color: bool) -> String {
+        let paint = |style: &str, text: &str| {
+            if color {
+                format!("{}{}{}", style, text, RESET)
+            } else {
+                text.to_string()
+            }
+        };
+
+        let mut out = format!(
+            "{}{}\n",
+            paint(RED, "error"),
+            paint(BOLD, format!(": {}", self.message).as_str())
+        );
+
+        let Some(span) = self.span else {
+            return self.render_footer(out, "", &paint);
+        };
+
+        let start = source.original_offset(span.start);
+        let end = source.original_offset(span.end).max(start);
+        let (line, column) = source.line_col(start);
+
+        let line_text = source.text.lines().nth(line - 1).unwrap_or_default();
+        let gutter = " ".repeat(line.to_string().len());
+
+        // Spans over several lines are only underlined up to the end of the first one.
+        let underline_len = source.text[start..end]
+            .lines()
+            .next()
+            .map_or(0, |text| text.chars().count())
+            .max(1);
+        let mut underline = format!("{}{}", " ".repeat(column - 1), "^".repeat(underline_len));
+
+        if let Some(label) = &self.label {
+            underline = format!("{} {}", underline, label);
+        }
+
+        out += format!(
+            "{}{} {}:{}:{}\n",
+            gutter,
+            paint(BLUE, "-->"),
+            source.name,
+            line,
+            column
+        )
+        .as_str();
+        out += format!("{} {}\n", gutter, paint(BLUE, "|")).as_str();
+        out += format!(
+            "{} {}\n",
+            paint(BLUE, format!("{} |", line).as_str()),
+            line_text
+        )
+        .as_str();
+        out += format!(
+            "{} {} {}\n",
+            gutter,
+            paint(BLUE, "|"),
+            paint(RED, underline.as_str())
+        )
+        .as_str();
+
+        self.render_footer(out, gutter.as_str(), &paint)
+    }
+
+    fn render_footer(
+        &self,
+        mut out: String,
+        gutter: &str,
+        paint: &dyn Fn(&str, &str) -> String,
+    ) -> String {
+        for note in &self.notes {
+            out += format!(
+                "{} {} {} {}\n",
+                gutter,
+                paint(BLUE, "="),
+                paint(BOLD, "note:"),
+                note
+            )
+            .as_str();
+        }
+
+        if let Some(help) = &self.help {
+            out += format!(
+                "{} {} {} {}\n",
+                gutter,
+                paint(BLUE, "="),
+                paint(BOLD, "help:"),
+                help
+            )
+            .as_str();
+        }
+
+        out
+    }
+}
+
+impl From<&LexError> for Diagnostic {
+    fn from(err: &LexError) -> Self {
+        match err {
+            LexError::UnrecognizedCharacter { .. } => {
+                Diagnostic::new(err.message(), Some(err.span()))
+                    .with_label("not valid bussin".to_string())
+            }
+            LexError::UnterminatedComment { .. } => {
+                Diagnostic::new(err.message(), Some(err.span()))
+                    .with_help("close it with \"*/\", once for every \"/*\" inside it".to_string())
+            }
+            LexError::UnterminatedString { .. } => Diagnostic::new(err.message(), Some(err.span()))
+                .with_label("string starts here".to_string())
+                .with_help("add the matching closing quote".to_string()),
+            LexError::InvalidNumber { .. } => Diagnostic::new(err.message(), Some(err.span()))
+                .with_note(
+                    "numbers look like 42, 1_000, 1.5e-3, 0xff, 0o17, 0b1010 or 123n".to_string(),
+                ),
+            LexError::InvalidEscape { .. } => Diagnostic::new(err.message(), Some(err.span()))
+                .with_note(
+                    "supported escapes are \\n \\t \\r \\0 \\\\ \\\" \\' \\` \\$ and \\u{...}"
+                        .to_string(),
+                ),
+        }
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(err: &ParseError) -> Self {
+        let diagnostic = Diagnostic::new(err.message(), Some(err.span()));
+
+        match err {
+            ParseError::Lex(lex_error) => lex_error.into(),
+            ParseError::ExpectedToken { expected, .. } => {
+                diagnostic.with_label(format!("expected {:?}", expected))
+            }
+            ParseError::UnexpectedToken { .. } => {
+                diagnostic.with_label("expected an expression".to_string())
+            }
+            ParseError::InvalidParameter { .. } => diagnostic.with_help(
+                "parameters must be plain names, like `fn add(a, b) { ... }`".to_string(),
+            ),
+            ParseError::MissingConstantValue { identifier, .. } => diagnostic.with_help(format!(
+                "give it a value, like `const {} = ...;`, or declare it with `let`",
+                identifier
+            )),
+            ParseError::MissingCatch { .. } => diagnostic
+                .with_label("expected \"catch\"".to_string())
+                .with_help("add a `catch { ... }` block after the `try` block".to_string()),
+            ParseError::OutsideLoop { .. } => {
+                diagnostic.with_label("not inside a loop".to_string())
+            }
+            ParseError::OutsideFunction { .. } => {
+                diagnostic.with_label("not inside a function".to_string())
+            }
+            ParseError::JumpOutOfTryExpr { .. } => diagnostic.with_help(
+                "move the `try` into a statement of its own, and assign to the variable inside it"
+                    .to_string(),
+            ),
+            ParseError::UnknownLabel { .. } => {
+                diagnostic.with_label("no loop with this label".to_string())
+            }
+            ParseError::InvalidLabel { .. } => diagnostic.with_help(
+                "labels can only name loops, like `outer: while (x) { ... }`".to_string(),
+            ),
+            ParseError::NumberOutOfRange { literal, .. } => {
+                let digits = literal.trim_start_matches('-').to_ascii_lowercase();
+                let diagnostic = diagnostic.with_label("doesn't fit in a number".to_string());
+
+                // Hex digits can be an `e`, but hex literals are always whole numbers.
+                if digits.starts_with("0x") || !digits.contains(['.', 'e']) {
+                    diagnostic.with_help(format!(
+                        "integers have to fit in 64 bits, make it a BigInt with `{}n`",
+                        literal
+                    ))
+                } else {
+                    diagnostic
+                }
+            }
+            ParseError::InvalidMemberProperty { .. } => diagnostic.with_help(
+                "use brackets to access computed properties, like `obj[key]`".to_string(),
+            ),
+        }
+    }
+}
+
+impl From<&(dyn Error + 'static)> for Diagnostic {
+    fn from(err: &(dyn Error + 'static)) -> Self {
+        if let Some(runtime_error) = err.downcast_ref::<RuntimeError>() {
+            return Diagnostic::new(runtime_error.message.clone(), Some(runtime_error.span));
+        }
+
+        if let Some(parse_error) = err.downcast_ref::<ParseError>() {
+            return parse_error.into();
+        }
+
+        if let Some(lex_error) = err.downcast_ref::<LexError>() {
+            return lex_error.into();
+        }
+
+        Diagnostic::new(err.to_string(), None)
+    }
+}
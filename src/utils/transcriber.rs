@@ -1,81 +1,182 @@
-use regex::Regex;
+// BSX words and what they mean in plain bussin. Only whole words are replaced.
+const REPLACEMENTS: &[(&str, &str); 23] = &[
+    ("rn", ";"),
+    ("be", "="),
+    ("lit", "let"),
+    ("mf", "const"),
+    ("waffle", "println"),
+    ("sus", "if"),
+    ("fake", "null"),
+    ("impostor", "else"),
+    ("nah", "!="),
+    ("fr", "=="),
+    ("btw", "&&"),
+    ("carenot", "||"),
+    ("bruh", "fn"),
+    ("nerd", "math"),
+    ("yall", "for"),
+    ("smol", "<"),
+    ("thicc", ">"),
+    ("nocap", "true"),
+    ("cap", "false"),
+    ("fuck_around", "try"),
+    ("find_out", "catch"),
+    ("clapback", "exec"),
+    ("yap", "input"),
+];
 
-// idk what to call it
-trait RegexReplace {
-    fn replace_fr(&self, target: &str, replacement: &str) -> String;
-    fn replace_regex(&self, pattern: &str, replacement: &str) -> String;
+// Type annotations are allowed in BSX, but bussin doesn't care about them.
+const TYPE_ANNOTATIONS: &[&str; 4] = &[": number", ": string", ": object", ": boolean"];
+
+/// A single replacement made while transcribing, as byte ranges in both versions of the code.
+#[derive(Debug, Clone)]
+struct Edit {
+    out_start: usize,
+    out_end: usize,
+    in_start: usize,
+    in_end: usize,
 }
 
-impl RegexReplace for String {
-    // I originally used regex here, but turns out, the regex crate's parse method
-    // does not have support for look-around, including look-ahead and look-behind.
-    // so replaced it with a chatgpt implementation. (I'm too dumb to
-    // implement whatever the hell that regex searched for.)
-    fn replace_fr(&self, target: &str, replacement: &str) -> String {
-        let mut result = String::with_capacity(self.len());
-
-        let mut iter = self.chars().peekable();
-
-        while let Some(c) = iter.next() {
-            if c.is_ascii_alphanumeric() {
-                let mut word = String::new();
-                word.push(c);
-
-                while let Some(&next_char) = iter.peek() {
-                    if next_char.is_ascii_alphanumeric() {
-                        word.push(iter.next().unwrap());
-                    } else {
-                        break;
-                    }
-                }
+/// Maps byte offsets in transcribed code back to the BSX code it came from, so errors can
+/// point at what the user actually wrote.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    edits: Vec<Edit>,
+}
+
+impl SourceMap {
+    pub fn original_offset(&self, offset: usize) -> usize {
+        let Some(edit) = self
+            .edits
+            .iter()
+            .rev()
+            .find(|edit| edit.out_start <= offset)
+        else {
+            return offset;
+        };
+
+        if offset < edit.out_end {
+            // Somewhere inside a replaced word, which can be longer or shorter than the original.
+            edit.in_start + (offset - edit.out_start).min(edit.in_end - edit.in_start)
+        } else {
+            edit.in_end + (offset - edit.out_end)
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Length of the comment at the start of `code`, if there is one. Block comments nest the
+/// same way they do in the lexer.
+fn comment_len(code: &str) -> Option<usize> {
+    if code.starts_with("//") {
+        return Some(code.find('\n').unwrap_or(code.len()));
+    }
+
+    if !code.starts_with("/*") {
+        return None;
+    }
+
+    let mut depth = 0;
+    let mut pos = 0;
+
+    while pos < code.len() {
+        if code[pos..].starts_with("/*") {
+            depth += 1;
+            pos += 2;
+        } else if code[pos..].starts_with("*/") {
+            depth -= 1;
+            pos += 2;
+
+            if depth == 0 {
+                return Some(pos);
+            }
+        } else {
+            pos += code[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    // Unterminated, which the lexer will complain about.
+    Some(code.len())
+}
+
+/// Transcribes BSX to bussin in a single pass, keeping track of every replacement made.
+pub fn transcribe(code: &str) -> (String, SourceMap) {
+    let mut result = String::with_capacity(code.len());
+    let mut map = SourceMap::default();
+    let mut pos = 0;
+    // The quote of the string we're in, if any.
+    let mut in_string: Option<char> = None;
+
+    while let Some(c) = code[pos..].chars().next() {
+        // Comments are copied as-is, but a "//" inside a string doesn't start one.
+        if let Some(len) = comment_len(&code[pos..]).filter(|_| in_string.is_none()) {
+            result.push_str(&code[pos..pos + len]);
+            pos += len;
+            continue;
+        }
+
+        match in_string {
+            None if c == '"' || c == '\'' || c == '`' => in_string = Some(c),
+            Some(quote) if c == quote => in_string = None,
+            Some(_) if c == '\\' => {
+                // Copy the escaped character too, so an escaped quote doesn't end the string.
+                let len = c.len_utf8() + code[pos + 1..].chars().next().map_or(0, char::len_utf8);
 
-                if word == target && iter.peek().map_or(true, |&c| !c.is_ascii_alphanumeric()) {
+                result.push_str(&code[pos..pos + len]);
+                pos += len;
+                continue;
+            }
+            _ => {}
+        }
+
+        if is_word_char(c) {
+            let len = code[pos..]
+                .find(|c: char| !is_word_char(c))
+                .unwrap_or(code.len() - pos);
+            let word = &code[pos..pos + len];
+
+            match REPLACEMENTS.iter().find(|(target, _)| *target == word) {
+                Some((_, replacement)) => {
+                    map.edits.push(Edit {
+                        out_start: result.len(),
+                        out_end: result.len() + replacement.len(),
+                        in_start: pos,
+                        in_end: pos + len,
+                    });
                     result.push_str(replacement);
-                } else {
-                    result.push_str(&word);
                 }
-            } else {
-                result.push(c);
+                None => result.push_str(word),
             }
+
+            pos += len;
+            continue;
         }
 
-        result
-    }
+        let annotation = TYPE_ANNOTATIONS.iter().find(|annotation| {
+            code[pos..].starts_with(**annotation)
+                && !code[pos + annotation.len()..]
+                    .chars()
+                    .next()
+                    .is_some_and(is_word_char)
+        });
 
-    fn replace_regex(&self, pattern: &str, replacement: &str) -> String {
-        let regex = Regex::new(pattern).expect("Invalid Regex");
+        if let Some(annotation) = annotation {
+            map.edits.push(Edit {
+                out_start: result.len(),
+                out_end: result.len(),
+                in_start: pos,
+                in_end: pos + annotation.len(),
+            });
+            pos += annotation.len();
+            continue;
+        }
 
-        regex.replace_all(self, replacement).to_string()
+        result.push(c);
+        pos += c.len_utf8();
     }
-}
 
-pub fn transcribe(code: String) -> String {
-    code.replace_fr(";", "!")
-        .replace_fr("rn", ";")
-        .replace_fr("be", "=")
-        .replace_fr("lit", "let")
-        .replace_fr("mf", "const")
-        .replace_fr("waffle", "println")
-        .replace_fr("sus", "if")
-        .replace_fr("fake", "null")
-        .replace_fr("impostor", "else")
-        .replace_fr("nah", "!=")
-        .replace_fr("fr", "==")
-        .replace_fr("btw", "&&")
-        .replace_fr("carenot", "|")
-        .replace_fr("bruh", "fn")
-        .replace_fr("nerd", "math")
-        .replace_fr("yall", "for")
-        .replace_fr("smol", "<")
-        .replace_fr("thicc", ">")
-        .replace_fr("nocap", "true")
-        .replace_fr("cap", "false")
-        .replace_fr("fuck_around", "try")
-        .replace_fr("find_out", "catch")
-        .replace_fr("clapback", "exec")
-        .replace_fr("yap", "input")
-        .replace_regex(": number", "")
-        .replace_regex(": string", "")
-        .replace_regex(": object", "")
-        .replace_regex(": boolean", "")
+    (result, map)
 }
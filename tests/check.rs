@@ -0,0 +1,82 @@
+//! Runs `check` on the files in `tests/check`, which are full of syntax errors, and makes
+//! sure every one of them is reported, at the right place.
+
+use std::{path::Path, process::Command};
+
+/// The `line:column` of every error `check` reports for `tests/check/<name>.bsn`, and the
+/// number of errors it says it found.
+fn check(name: &str) -> (Vec<String>, String) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/check")
+        .join(format!("{}.bsn", name));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bussin-rs"))
+        .arg("check")
+        .arg(&path)
+        .arg("--no-color")
+        .output()
+        .expect("Failed to run bussin");
+
+    assert!(!output.status.success(), "{}.bsn has no errors", name);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let locations = stderr
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("--> "))
+        .map(|location| {
+            let mut parts = location.rsplitn(3, ':');
+            let column = parts.next().unwrap();
+            let line = parts.next().unwrap();
+
+            format!("{}:{}", line, column)
+        })
+        .collect();
+    let summary = stderr
+        .lines()
+        .last()
+        .and_then(|line| line.rsplit(": ").next())
+        .unwrap_or_default()
+        .to_string();
+
+    (locations, summary)
+}
+
+#[test]
+fn recovers_after_semicolons() {
+    let (locations, summary) = check("semicolons");
+
+    assert_eq!(locations, ["1:5", "2:9", "3:5"]);
+    assert_eq!(summary, "3 error(s) found.");
+}
+
+#[test]
+fn recovers_at_new_lines() {
+    let (locations, summary) = check("newlines");
+
+    assert_eq!(locations, ["1:5", "3:1", "3:11"]);
+    assert_eq!(summary, "3 error(s) found.");
+}
+
+#[test]
+fn recovers_inside_blocks() {
+    let (locations, summary) = check("blocks");
+
+    assert_eq!(locations, ["2:9", "4:1", "6:14", "7:1", "9:9"]);
+    assert_eq!(summary, "5 error(s) found.");
+}
+
+#[test]
+fn rejects_integers_outside_64_bits() {
+    let (locations, summary) = check("numbers");
+
+    assert_eq!(locations, ["3:15", "4:11", "5:14", "7:13"]);
+    assert_eq!(summary, "4 error(s) found.");
+}
+
+#[test]
+fn rejects_jumps_out_of_try_values() {
+    let (locations, summary) = check("try_value");
+
+    assert_eq!(locations, ["2:19", "5:19", "6:31"]);
+    assert_eq!(summary, "3 error(s) found.");
+}
@@ -0,0 +1,42 @@
+//! Runs the programs in `tests/diagnostics`, which all fail, and compares the errors they
+//! print with the `.err` file next to each of them.
+
+use std::{fs, path::Path, process::Command};
+
+fn run_failing(file: &str) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/diagnostics");
+    let name = file.rsplit_once('.').map_or(file, |(name, _)| name);
+    let expected = fs::read_to_string(dir.join(format!("{}.err", name)))
+        .expect("Failed to read expected errors");
+
+    // Run from inside the directory, so the file name in the errors is the same everywhere.
+    let output = Command::new(env!("CARGO_BIN_EXE_bussin-rs"))
+        .current_dir(&dir)
+        .arg(file)
+        .arg("--no-color")
+        .output()
+        .expect("Failed to run bussin");
+
+    assert!(!output.status.success(), "{} didn't fail", file);
+    assert_eq!(String::from_utf8_lossy(&output.stderr), expected);
+}
+
+#[test]
+fn lex_error() {
+    run_failing("lex_error.bsn");
+}
+
+#[test]
+fn parse_error_at_end_of_file() {
+    run_failing("parse_error.bsn");
+}
+
+#[test]
+fn runtime_error() {
+    run_failing("runtime_error.bsn");
+}
+
+#[test]
+fn bsx_error() {
+    run_failing("bsx_error.bsx");
+}
@@ -0,0 +1,110 @@
+//! Runs the programs in `tests/scripts`, plain bussin or BSX, and compares what they print
+//! with the `.out` file next to each of them.
+
+use std::{fs, path::Path, process::Command};
+
+fn run_script(name: &str) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scripts");
+    let expected = fs::read_to_string(dir.join(format!("{}.out", name)))
+        .expect("Failed to read expected output");
+
+    let script = ["bsn", "bsx"]
+        .iter()
+        .map(|extension| dir.join(format!("{}.{}", name, extension)))
+        .find(|path| path.exists())
+        .expect("Failed to find script");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bussin-rs"))
+        .arg(script)
+        .arg("--no-color")
+        .output()
+        .expect("Failed to run bussin");
+
+    assert!(
+        output.status.success(),
+        "{} failed:\n{}",
+        name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+}
+
+#[test]
+fn counters() {
+    run_script("counters");
+}
+
+#[test]
+fn closures() {
+    run_script("closures");
+}
+
+#[test]
+fn nested_loops() {
+    run_script("nested_loops");
+}
+
+#[test]
+fn references() {
+    run_script("references");
+}
+
+#[test]
+fn equality() {
+    run_script("equality");
+}
+
+#[test]
+fn objects() {
+    run_script("objects");
+}
+
+#[test]
+fn members() {
+    run_script("members");
+}
+
+#[test]
+fn methods() {
+    run_script("methods");
+}
+
+#[test]
+fn comments() {
+    run_script("comments");
+}
+
+#[test]
+fn templates() {
+    run_script("templates");
+}
+
+#[test]
+fn integers() {
+    run_script("integers");
+}
+
+#[test]
+fn bigint() {
+    run_script("bigint");
+}
+
+#[test]
+fn operators() {
+    run_script("operators");
+}
+
+#[test]
+fn logical() {
+    run_script("logical");
+}
+
+#[test]
+fn unary() {
+    run_script("unary");
+}
+
+#[test]
+fn try_catch() {
+    run_script("try_catch");
+}